@@ -78,7 +78,7 @@ fn parse_argument(pair: Pair<Rule>, file: &str) -> Result<Argument, Box<RuleErro
             // at this moment we are on [argument]
             let mut childs = pair.into_inner();
             let DescriptionAndNext(description, next) =
-                parse_description_and_continue(&mut childs, file);
+                parse_description_and_continue(&mut childs, file)?;
             // at this moment we are on [identifier, value]
             let name = parse_identifier(next.unwrap(), file)?;
             let value = parse_value_type(childs.next().unwrap(), file)?;
@@ -135,7 +135,7 @@ mod tests {
     #[test]
     fn test_accepts_description() {
         assert_eq!(
-            parse_input("(\"\"\" my description \"\"\"arg: String)"),
+            parse_input("(\"\"\"my description\"\"\"arg: String)"),
             Ok(vec![Argument::string("arg").description("my description")])
         );
     }
@@ -179,6 +179,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_null_and_negative_default_values() {
+        assert_eq!(
+            parse_input("(a: String = null b: Int = -1 c: Boolean = true)"),
+            Ok(vec![
+                Argument::string("a").default(ArgumentDefaultValue::ValueData(ValueData::null())),
+                Argument::int("b").default(ArgumentDefaultValue::ValueData(ValueData::int(-1))),
+                Argument::boolean("c")
+                    .default(ArgumentDefaultValue::ValueData(ValueData::boolean(true))),
+            ])
+        );
+    }
+
     #[test]
     fn test_accept_directives() {
         assert_eq!(
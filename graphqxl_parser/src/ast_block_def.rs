@@ -35,7 +35,7 @@ impl Display for BlockDefType {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum BlockEntry {
-    Field(BlockField),
+    Field(Box<BlockField>),
     SpreadRef(ModifiedRef),
 }
 
@@ -109,7 +109,7 @@ impl BlockDef {
     }
 
     pub fn field(&mut self, field: BlockField) -> Self {
-        self.entries.push(BlockEntry::Field(field));
+        self.entries.push(BlockEntry::Field(Box::new(field)));
         self.clone()
     }
 
@@ -179,7 +179,7 @@ fn _parse_block_def(
                         }
                         _ => {
                             let field = parse_block_field(pair.clone(), file)?;
-                            entries.push(BlockEntry::Field(field));
+                            entries.push(BlockEntry::Field(Box::new(field)));
                         }
                     }
                 }
@@ -250,7 +250,7 @@ mod tests {
     #[test]
     fn test_type_description_works() {
         assert_eq!(
-            parse_input("\"\"\" my description \"\"\"type MyType { arg: String }"),
+            parse_input("\"\"\"my description\"\"\"type MyType { arg: String }"),
             Ok(BlockDef::type_def("MyType")
                 .description("my description")
                 .field(BlockField::build("arg").string()))
@@ -260,7 +260,7 @@ mod tests {
     #[test]
     fn test_input_description_works() {
         assert_eq!(
-            parse_input("\"\"\" my description \"\"\"input MyInput { arg: String }"),
+            parse_input("\"\"\"my description\"\"\"input MyInput { arg: String }"),
             Ok(BlockDef::input_def("MyInput")
                 .description("my description")
                 .field(BlockField::build("arg").string()))
@@ -270,7 +270,7 @@ mod tests {
     #[test]
     fn test_enum_description_works() {
         assert_eq!(
-            parse_input("\"\"\" my description \"\"\"enum MyEnum { arg }"),
+            parse_input("\"\"\"my description\"\"\"enum MyEnum { arg }"),
             Ok(BlockDef::enum_def("MyEnum")
                 .description("my description")
                 .field(BlockField::build("arg")))
@@ -280,7 +280,7 @@ mod tests {
     #[test]
     fn test_interface_description_works() {
         assert_eq!(
-            parse_input("\"\"\" my description \"\"\"interface MyInterface { arg: String }"),
+            parse_input("\"\"\"my description\"\"\"interface MyInterface { arg: String }"),
             Ok(BlockDef::interface_def("MyInterface")
                 .description("my description")
                 .field(BlockField::build("arg").string()))
@@ -300,7 +300,7 @@ mod tests {
     #[test]
     fn test_description_variables_with_description_works() {
         assert_eq!(
-            parse_input("${foo: \"bar\"}\"\"\" my description \"\"\"type MyType { arg: String }"),
+            parse_input("${foo: \"bar\"}\"\"\"my description\"\"\"type MyType { arg: String }"),
             Ok(BlockDef::type_def("MyType")
                 .description("my description")
                 .description_variable(("foo", "bar"))
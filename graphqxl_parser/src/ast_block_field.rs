@@ -15,6 +15,8 @@ pub struct BlockField {
     pub value_type: Option<ValueType>,
     pub args: Vec<Argument>,
     pub directives: Vec<Directive>,
+    /// Whether the field replaces one with the same name that comes from a spread
+    pub overrides: bool,
 }
 
 impl BlockField {
@@ -69,19 +71,31 @@ impl BlockField {
         self.directives.push(directive);
         self.clone()
     }
+
+    pub fn overrides(&mut self) -> Self {
+        self.overrides = true;
+        self.clone()
+    }
 }
 
 fn _parse_block_field(pair: Pair<Rule>, file: &str) -> Result<BlockField, Box<RuleError>> {
     let span = OwnedSpan::from(pair.as_span(), file);
     // at this moment we are on [type_field|input_field], both will work
     let mut pairs = pair.into_inner();
-    // at this moment we are on [description?, identifier, args?, value?]
-    let DescriptionAndNext(description, next) = parse_description_and_continue(&mut pairs, file);
+    // at this moment we are on [description?, override?, identifier, args?, value?]
+    let DescriptionAndNext(description, mut next) =
+        parse_description_and_continue(&mut pairs, file)?;
+    let mut overrides = false;
+    if let Some(Rule::field_override) = next.as_ref().map(|pair| pair.as_rule()) {
+        overrides = true;
+        next = pairs.next();
+    }
     let name = parse_identifier(next.unwrap(), file)?;
     let mut block_field = BlockField {
         span,
         name,
         description,
+        overrides,
         ..Default::default()
     };
     let value_or_args_or_nothing = pairs.next();
@@ -157,7 +171,7 @@ mod tests {
     #[test]
     fn test_accepts_description() {
         assert_eq!(
-            parse_with_args_input("\"\"\" my description \"\"\" field(arg: String): String"),
+            parse_with_args_input("\"\"\"my description\"\"\" field(arg: String): String"),
             Ok(BlockField::build("field")
                 .string()
                 .arg(Argument::string("arg"))
@@ -241,6 +255,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parses_overrides() {
+        assert_eq!(
+            parse_with_args_input("\"desc\" override id(a: Int): ID!"),
+            Ok(BlockField::build("id")
+                .description("desc")
+                .arg(Argument::int("a"))
+                .value_type(ValueType::id().non_nullable())
+                .overrides())
+        );
+        assert_eq!(
+            parse_without_args_input("override id: ID"),
+            Ok(BlockField::build("id")
+                .value_type(ValueType::id())
+                .overrides())
+        );
+    }
+
+    #[test]
+    fn test_override_can_be_a_field_name() {
+        assert_eq!(
+            parse_with_args_input("override : Int"),
+            Ok(BlockField::build("override").int())
+        );
+        assert_eq!(
+            parse_without_args_input("overrideId: Int"),
+            Ok(BlockField::build("overrideId").int())
+        );
+    }
+
     #[test]
     fn test_do_not_parse_invalid() {
         parse_with_args_input("field: [String!!").unwrap_err();
@@ -1,17 +1,15 @@
 use crate::parser::{Rule, RuleError};
-use crate::utils::unknown_rule_error;
+use crate::utils::{block_string_value, custom_error, string_value, unknown_rule_error};
 use pest::iterators::{Pair, Pairs};
 
 pub(crate) fn parse_description(pair: Pair<Rule>, _file: &str) -> Result<String, Box<RuleError>> {
     match pair.as_rule() {
         Rule::description => {
-            let mut result = "".to_string();
-            let trimmed = pair.as_str().trim_matches('\"');
-            for line in trimmed.split('\n') {
-                result += line.trim();
-                result += "\n";
+            let str = pair.as_str();
+            if str.starts_with("\"\"\"") {
+                return Ok(block_string_value(&str[3..str.len() - 3]));
             }
-            Ok(result.trim().to_string())
+            string_value(&str[1..str.len() - 1]).map_err(|err| custom_error(pair, &err))
         }
         _unknown => Err(unknown_rule_error(pair, "description")),
     }
@@ -23,18 +21,18 @@ pub(crate) struct DescriptionAndNext<'a>(pub(crate) String, pub(crate) Option<Pa
 pub(crate) fn parse_description_and_continue<'a>(
     pairs: &mut Pairs<'a, Rule>,
     file: &str,
-) -> DescriptionAndNext<'a> {
+) -> Result<DescriptionAndNext<'a>, Box<RuleError>> {
     let mut pair_opt = pairs.next();
     let mut description = "".to_string();
     if let Some(pair) = pair_opt {
         if let Rule::description = pair.as_rule() {
-            description = parse_description(pair, file).unwrap();
+            description = parse_description(pair, file)?;
             pair_opt = pairs.next();
         } else {
             pair_opt = Some(pair)
         }
     }
-    DescriptionAndNext(description, pair_opt)
+    Ok(DescriptionAndNext(description, pair_opt))
 }
 
 #[cfg(test)]
@@ -49,7 +47,7 @@ mod tests {
     #[test]
     fn test_parses_one_line_description() {
         let description = parse_input("\"This is a \\ndescription 123 \"").unwrap();
-        assert_eq!(description, "This is a \\ndescription 123");
+        assert_eq!(description, "This is a \ndescription 123 ");
     }
 
     #[test]
@@ -65,25 +63,65 @@ mod tests {
     #[test]
     fn test_parses_multiline_description() {
         let description = parse_input("\"\"\" This is a \ndescription 123 \"\"\"").unwrap();
-        assert_eq!(description, "This is a\ndescription 123");
+        assert_eq!(description, " This is a \ndescription 123 ");
     }
 
     #[test]
     fn test_multiline_description_accepts_double_quote() {
         let description = parse_input("\"\"\" This is a \"description 123 \"\"\"").unwrap();
-        assert_eq!(description, "This is a \"description 123");
+        assert_eq!(description, " This is a \"description 123 ");
     }
 
     #[test]
     fn test_multiline_description_accepts_escaped_double_quote() {
         let description = parse_input("\"\"\" This is a \\\"description 123 \"\"\"").unwrap();
-        assert_eq!(description, "This is a \\\"description 123");
+        assert_eq!(description, " This is a \\\"description 123 ");
     }
 
     #[test]
     fn test_parses_multiline_description_trimming_indent_spaces() {
-        let description = parse_input("\"\"\" This is a \n    description 123 \"\"\"").unwrap();
-        assert_eq!(description, "This is a\ndescription 123");
+        let description = parse_input("\"\"\"This is a \n    description 123\n    \"\"\"").unwrap();
+        assert_eq!(description, "This is a \ndescription 123");
+    }
+
+    #[test]
+    fn test_multiline_description_keeps_relative_indentation_and_blank_lines() {
+        let description = parse_input(
+            "\"\"\"
+    Example:
+
+        query {
+          user
+        }
+    \"\"\"",
+        )
+        .unwrap();
+        assert_eq!(description, "Example:\n\n    query {\n      user\n    }");
+    }
+
+    #[test]
+    fn test_multiline_description_accepts_escaped_triple_quotes() {
+        let description = parse_input("\"\"\" a \\\"\"\" b \"\"\"").unwrap();
+        assert_eq!(description, " a \"\"\" b ");
+    }
+
+    #[test]
+    fn test_multiline_description_keeps_whitespace_of_non_blank_lines() {
+        let description = parse_input("\"\"\"  a  \n    b\t\n      c\n  \n\"\"\"").unwrap();
+        assert_eq!(description, "  a  \nb\t\n  c");
+    }
+
+    #[test]
+    fn test_one_line_description_keeps_surrounding_whitespace() {
+        let description = parse_input("\"  a \\t\"").unwrap();
+        assert_eq!(description, "  a \t");
+    }
+
+    #[test]
+    fn test_one_line_description_resolves_unicode_escapes() {
+        let description = parse_input("\"caf\\u00e9 \\uD83D\\uDE00\"").unwrap();
+        assert_eq!(description, "café 😀");
+        parse_input("\"\\uD83D\"").unwrap_err();
     }
 
     #[test]
@@ -1,6 +1,6 @@
 use crate::ast_identifier::parse_identifier;
 use crate::parser::{Rule, RuleError};
-use crate::utils::unknown_rule_error;
+use crate::utils::{custom_error, string_value, unknown_rule_error};
 use crate::OwnedSpan;
 use pest::iterators::Pair;
 use std::collections::HashMap;
@@ -40,7 +40,10 @@ pub(crate) fn parse_description_variables(
                 let second = grand_childs.next().unwrap();
                 let identifier = parse_identifier(first, file)?;
                 let str = second.as_str();
-                let value = str[1..str.len() - 1].to_string(); // trim ""
+                let value = match string_value(&str[1..str.len() - 1]) {
+                    Ok(value) => value,
+                    Err(err) => return Err(custom_error(second, &err)),
+                };
                 variables.insert(identifier.id, value);
             }
 
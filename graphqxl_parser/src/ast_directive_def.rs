@@ -56,7 +56,7 @@ pub(crate) fn parse_directive_def(
             // [identifier, arguments?, repeatable?, ...locations]
             let mut childs = pair.into_inner();
             let DescriptionAndNext(description, next) =
-                parse_description_and_continue(&mut childs, file);
+                parse_description_and_continue(&mut childs, file)?;
             let name = parse_identifier(next.unwrap(), file)?;
             let mut next = childs.next().unwrap();
             let mut arguments = Vec::new();
@@ -114,7 +114,7 @@ mod tests {
     #[test]
     fn test_accepts_description() {
         assert_eq!(
-            parse_input("\"\"\" my description \"\"\"directive @dir on QUERY"),
+            parse_input("\"\"\"my description\"\"\"directive @dir on QUERY"),
             Ok(DirectiveDef::build("dir")
                 .description("my description")
                 .location(DirectiveLocation::Query))
@@ -1,13 +1,15 @@
 use pest::iterators::Pair;
 
 use crate::parser::{Rule, RuleError};
-use crate::utils::unknown_rule_error;
-use crate::{parse_identifier, Identifier, OwnedSpan};
+use crate::utils::{custom_error, unknown_rule_error};
+use crate::{parse_identifier, parse_value_type, Identifier, OwnedSpan, ValueType};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Generic {
     pub span: OwnedSpan,
     pub args: Vec<Identifier>,
+    /// Default value for each one of the args, only trailing args can have one
+    pub defaults: Vec<Option<ValueType>>,
 }
 
 impl Generic {
@@ -15,13 +17,29 @@ impl Generic {
         Generic {
             span: OwnedSpan::default(),
             args: vec![Identifier::from(name)],
+            defaults: vec![None],
         }
     }
 
     pub fn arg(&mut self, name: &str) -> Self {
         self.args.push(Identifier::from(name));
+        self.defaults.push(None);
         self.clone()
     }
+
+    pub fn arg_with_default(&mut self, name: &str, default: ValueType) -> Self {
+        self.args.push(Identifier::from(name));
+        self.defaults.push(Some(default));
+        self.clone()
+    }
+
+    /// Number of args that must be provided when instantiating the generic
+    pub fn required_args(&self) -> usize {
+        self.defaults
+            .iter()
+            .filter(|default| default.is_none())
+            .count()
+    }
 }
 
 pub(crate) fn parse_generic(pair: Pair<Rule>, file: &str) -> Result<Generic, Box<RuleError>> {
@@ -30,11 +48,32 @@ pub(crate) fn parse_generic(pair: Pair<Rule>, file: &str) -> Result<Generic, Box
             let span = OwnedSpan::from(pair.as_span(), file);
             let childs = pair.into_inner();
             let mut args = Vec::new();
-            for name in childs {
-                args.push(parse_identifier(name, file)?);
+            let mut defaults = Vec::new();
+            for child in childs {
+                let mut generic_arg = child.clone().into_inner();
+                let name = parse_identifier(generic_arg.next().unwrap(), file)?;
+                let default = match generic_arg.next() {
+                    Some(value_type) => Some(parse_value_type(value_type, file)?),
+                    None => None,
+                };
+                if default.is_none() && defaults.iter().any(Option::is_some) {
+                    return Err(custom_error(
+                        child,
+                        &format!(
+                            "generic parameter {} needs a default value because it follows one with a default",
+                            name.id
+                        ),
+                    ));
+                }
+                args.push(name);
+                defaults.push(default);
             }
 
-            Ok(Generic { span, args })
+            Ok(Generic {
+                span,
+                args,
+                defaults,
+            })
         }
         _unknown => Err(unknown_rule_error(pair, "generic")),
     }
@@ -65,6 +104,24 @@ mod tests {
         assert_eq!(parse_input("<T, C>"), Ok(Generic::from("T").arg("C")))
     }
 
+    #[test]
+    fn test_parses_generics_with_defaults() {
+        assert_eq!(
+            parse_input("<T, C = String, D = [Int!]>"),
+            Ok(Generic::from("T")
+                .arg_with_default("C", ValueType::string())
+                .arg_with_default("D", ValueType::int().non_nullable().array()))
+        )
+    }
+
+    #[test]
+    fn test_do_not_parse_required_arg_after_default() {
+        let err = parse_input("<T = String, C>").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("generic parameter C needs a default"))
+    }
+
     #[test]
     fn test_do_not_parse_incorrectly_formed_generic() {
         parse_input("T>").unwrap_err();
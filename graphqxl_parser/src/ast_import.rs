@@ -1,12 +1,36 @@
+use crate::ast_identifier::parse_identifier;
 use crate::parser::{Rule, RuleError};
 use crate::utils::unknown_rule_error;
-use crate::OwnedSpan;
+use crate::{Identifier, OwnedSpan};
 use pest::iterators::Pair;
 
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ImportItem {
+    pub span: OwnedSpan,
+    pub name: Identifier,
+    pub alias: Option<Identifier>,
+}
+
+impl ImportItem {
+    pub fn from(name: &str) -> Self {
+        Self {
+            name: Identifier::from(name),
+            ..Default::default()
+        }
+    }
+
+    pub fn alias(&mut self, alias: &str) -> Self {
+        self.alias = Some(Identifier::from(alias));
+        self.clone()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct Import {
     pub file_name: String,
     pub span: OwnedSpan,
+    /// Selected names, an empty list means that everything is imported
+    pub items: Vec<ImportItem>,
 }
 
 impl From<&str> for Import {
@@ -14,7 +38,31 @@ impl From<&str> for Import {
         Self {
             file_name: file_name.to_string(),
             span: OwnedSpan::default(),
+            items: Vec::new(),
+        }
+    }
+}
+
+impl Import {
+    pub fn item(&mut self, item: ImportItem) -> Self {
+        self.items.push(item);
+        self.clone()
+    }
+}
+
+fn parse_import_item(pair: Pair<Rule>, file: &str) -> Result<ImportItem, Box<RuleError>> {
+    match pair.as_rule() {
+        Rule::import_item => {
+            let span = OwnedSpan::from(pair.as_span(), file);
+            let mut childs = pair.into_inner();
+            let name = parse_identifier(childs.next().unwrap(), file)?;
+            let alias = match childs.nth(1) {
+                Some(child) => Some(parse_identifier(child, file)?),
+                None => None,
+            };
+            Ok(ImportItem { span, name, alias })
         }
+        _unknown => Err(unknown_rule_error(pair, "import_item")),
     }
 }
 
@@ -22,11 +70,28 @@ pub(crate) fn parse_import(pair: Pair<Rule>, file: &str) -> Result<Import, Box<R
     match pair.as_rule() {
         Rule::import => {
             let span = OwnedSpan::from(pair.as_span(), file);
-            let rule = pair.into_inner().next().unwrap();
+            let mut items = Vec::new();
+            let mut file_name = String::new();
+            for child in pair.into_inner() {
+                match child.as_rule() {
+                    Rule::import_selection => {
+                        for item in child.into_inner() {
+                            items.push(parse_import_item(item, file)?);
+                        }
+                    }
+                    Rule::filename => {
+                        file_name = child.as_str().trim_matches('\"').to_string();
+                    }
+                    _unknown => {
+                        return Err(unknown_rule_error(child, "import_selection or filename"))
+                    }
+                }
+            }
 
             Ok(Import {
-                file_name: rule.as_str().trim_matches('\"').to_string(),
+                file_name,
                 span,
+                items,
             })
         }
         _ => Err(unknown_rule_error(pair, "import")),
@@ -54,4 +119,34 @@ mod tests {
     fn test_does_not_parse_invalid_import() {
         parse_input("import my_file").unwrap_err();
     }
+
+    #[test]
+    fn test_parses_selective_import() {
+        assert_eq!(
+            parse_input("import { User, _BaseEntity } from \"users\"").unwrap(),
+            Import::from("users")
+                .item(ImportItem::from("User"))
+                .item(ImportItem::from("_BaseEntity"))
+        )
+    }
+
+    #[test]
+    fn test_parses_aliased_import() {
+        assert_eq!(
+            parse_input("import { User as AccountUser assets } from \"users\"").unwrap(),
+            Import::from("users")
+                .item(ImportItem::from("User").alias("AccountUser"))
+                .item(ImportItem::from("assets"))
+        )
+    }
+
+    #[test]
+    fn test_does_not_parse_selective_import_without_from() {
+        parse_input("import { User } \"users\"").unwrap_err();
+    }
+
+    #[test]
+    fn test_does_not_parse_empty_selective_import() {
+        parse_input("import {} from \"users\"").unwrap_err();
+    }
 }
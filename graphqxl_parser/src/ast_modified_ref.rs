@@ -1,14 +1,16 @@
 use crate::ast_expandable_ref::{parse_expandable_ref, ExpandableRef};
 use crate::parser::{Rule, RuleError};
 use crate::utils::unknown_rule_error;
-use crate::OwnedSpan;
+use crate::{Identifier, OwnedSpan};
 use pest::iterators::Pair;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ModifiedRef {
     Required(Box<ModifiedRef>, OwnedSpan),
     Optional(Box<ModifiedRef>, OwnedSpan),
-    ExpandableRef(ExpandableRef),
+    Omit(Box<ModifiedRef>, Vec<Identifier>, OwnedSpan),
+    Pick(Box<ModifiedRef>, Vec<Identifier>, OwnedSpan),
+    ExpandableRef(Box<ExpandableRef>),
 }
 
 impl ModifiedRef {
@@ -16,18 +18,31 @@ impl ModifiedRef {
         match self {
             ModifiedRef::Required(_, span) => span,
             ModifiedRef::Optional(_, span) => span,
+            ModifiedRef::Omit(_, _, span) => span,
+            ModifiedRef::Pick(_, _, span) => span,
             ModifiedRef::ExpandableRef(r) => &r.span,
         }
     }
+
+    /// The name of the referenced definition, below all the modifiers
+    pub fn identifier(&self) -> &Identifier {
+        match self {
+            ModifiedRef::Required(inner, _)
+            | ModifiedRef::Optional(inner, _)
+            | ModifiedRef::Omit(inner, _, _)
+            | ModifiedRef::Pick(inner, _, _) => inner.identifier(),
+            ModifiedRef::ExpandableRef(r) => &r.identifier,
+        }
+    }
 }
 
 impl ModifiedRef {
     pub fn build(name: &str) -> Self {
-        Self::ExpandableRef(ExpandableRef::from(name))
+        Self::ExpandableRef(Box::new(ExpandableRef::from(name)))
     }
 
     pub fn expandable_ref(expandable_ref: ExpandableRef) -> Self {
-        Self::ExpandableRef(expandable_ref)
+        Self::ExpandableRef(Box::new(expandable_ref))
     }
 
     pub fn optional(&mut self) -> Self {
@@ -37,6 +52,29 @@ impl ModifiedRef {
     pub fn required(&mut self) -> Self {
         ModifiedRef::Required(Box::new(self.clone()), OwnedSpan::default())
     }
+
+    pub fn omit(&mut self, fields: &[&str]) -> Self {
+        let fields = fields.iter().map(|field| Identifier::from(field)).collect();
+        ModifiedRef::Omit(Box::new(self.clone()), fields, OwnedSpan::default())
+    }
+
+    pub fn pick(&mut self, fields: &[&str]) -> Self {
+        let fields = fields.iter().map(|field| Identifier::from(field)).collect();
+        ModifiedRef::Pick(Box::new(self.clone()), fields, OwnedSpan::default())
+    }
+}
+
+fn parse_field_selection(pair: Pair<Rule>, file: &str) -> Result<Vec<Identifier>, Box<RuleError>> {
+    match pair.as_rule() {
+        Rule::field_selection => Ok(pair
+            .into_inner()
+            .map(|child| Identifier {
+                id: child.as_str().trim_matches('\"').to_string(),
+                span: OwnedSpan::from(child.as_span(), file),
+            })
+            .collect()),
+        _unknown => Err(unknown_rule_error(pair, "field_selection")),
+    }
 }
 
 pub(crate) fn parse_modified_ref(
@@ -63,12 +101,30 @@ pub(crate) fn parse_modified_ref(
                         span,
                     ))
                 }
-                Rule::expandable_ref => Ok(ModifiedRef::ExpandableRef(parse_expandable_ref(
-                    first, file,
-                )?)),
+                Rule::omit_modifier => {
+                    let second = childs.next().unwrap();
+                    let third = childs.next().unwrap();
+                    Ok(ModifiedRef::Omit(
+                        Box::new(parse_modified_ref(second, file)?),
+                        parse_field_selection(third, file)?,
+                        span,
+                    ))
+                }
+                Rule::pick_modifier => {
+                    let second = childs.next().unwrap();
+                    let third = childs.next().unwrap();
+                    Ok(ModifiedRef::Pick(
+                        Box::new(parse_modified_ref(second, file)?),
+                        parse_field_selection(third, file)?,
+                        span,
+                    ))
+                }
+                Rule::expandable_ref => Ok(ModifiedRef::ExpandableRef(Box::new(
+                    parse_expandable_ref(first, file)?,
+                ))),
                 _unknown => Err(unknown_rule_error(
                     first,
-                    "required_modifier, optional_modifier or expandable_ref",
+                    "required_modifier, optional_modifier, omit_modifier, pick_modifier or expandable_ref",
                 )),
             }
         }
@@ -107,6 +163,22 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_parses_omit_modified_ref() {
+        assert_eq!(
+            parse_input("Omit<MyType, \"id\" | \"createdAt\">"),
+            Ok(ModifiedRef::build("MyType").omit(&["id", "createdAt"]))
+        )
+    }
+
+    #[test]
+    fn test_parses_nested_pick_modified_ref() {
+        assert_eq!(
+            parse_input("Optional<Pick<MyType \"name\">>"),
+            Ok(ModifiedRef::build("MyType").pick(&["name"]).optional())
+        )
+    }
+
     #[test]
     fn test_parses_required_modified_ref_with_generic() {
         assert_eq!(
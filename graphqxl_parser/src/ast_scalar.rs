@@ -39,22 +39,21 @@ impl Scalar {
 }
 
 fn _parse_scalar(pair: Pair<Rule>, file: &str, extend: bool) -> Result<Scalar, Box<RuleError>> {
-            let span = OwnedSpan::from(pair.as_span(), file);
-            let mut childs = pair.into_inner();
-            let DescriptionAndNext(description, next) =
-                parse_description_and_continue(&mut childs, file);
-            let name = parse_identifier(next.unwrap(), file)?;
-            let mut directives = Vec::new();
-            for child in childs {
-                directives.push(parse_directive(child, file)?);
-            }
-            Ok(Scalar {
-                extend,
-                span,
-                name,
-                description,
-                directives,
-            })
+    let span = OwnedSpan::from(pair.as_span(), file);
+    let mut childs = pair.into_inner();
+    let DescriptionAndNext(description, next) = parse_description_and_continue(&mut childs, file)?;
+    let name = parse_identifier(next.unwrap(), file)?;
+    let mut directives = Vec::new();
+    for child in childs {
+        directives.push(parse_directive(child, file)?);
+    }
+    Ok(Scalar {
+        extend,
+        span,
+        name,
+        description,
+        directives,
+    })
 }
 
 pub(crate) fn parse_scalar(pair: Pair<Rule>, file: &str) -> Result<Scalar, Box<RuleError>> {
@@ -77,7 +76,7 @@ mod tests {
     #[test]
     fn test_accepts_description() {
         assert_eq!(
-            parse_input("\"\"\" my description \"\"\" scalar MyScalar"),
+            parse_input("\"\"\"my description\"\"\" scalar MyScalar"),
             Ok(Scalar::build("MyScalar").description("my description"))
         );
     }
@@ -61,14 +61,12 @@ enum SchemaKey {
 
 fn parse_schema_key(pair: Pair<Rule>, _file: &str) -> Result<SchemaKey, Box<RuleError>> {
     match pair.as_rule() {
-        Rule::schema_key => {
-            return match pair.as_str() {
-                "query" => Ok(SchemaKey::Query),
-                "mutation" => Ok(SchemaKey::Mutation),
-                "subscription" => Ok(SchemaKey::Subscription),
-                _ => unreachable!(),
-            }
-        }
+        Rule::schema_key => match pair.as_str() {
+            "query" => Ok(SchemaKey::Query),
+            "mutation" => Ok(SchemaKey::Mutation),
+            "subscription" => Ok(SchemaKey::Subscription),
+            _ => unreachable!(),
+        },
         _unknown => Err(unknown_rule_error(pair, "schema_key")),
     }
 }
@@ -81,7 +79,7 @@ fn _parse_schema(pair: Pair<Rule>, file: &str, extend: bool) -> Result<Schema, B
     let mut directives = vec![];
     let mut childs = pair.into_inner();
     let DescriptionAndNext(description, mut next_opt) =
-        parse_description_and_continue(&mut childs, file);
+        parse_description_and_continue(&mut childs, file)?;
 
     while let Some(next) = next_opt.clone() {
         if Rule::directive != next.as_rule() {
@@ -89,7 +87,7 @@ fn _parse_schema(pair: Pair<Rule>, file: &str, extend: bool) -> Result<Schema, B
         }
         directives.push(parse_directive(next, file)?);
         next_opt = childs.next();
-    } 
+    }
 
     if let Some(next) = next_opt {
         for field in next.into_inner() {
@@ -168,10 +166,10 @@ mod tests {
     #[test]
     fn test_parses_query_with_description() {
         assert_eq!(
-            parse_input("\"\"\" my \"description \"\"\"schema { query: Query }"),
+            parse_input("\"\"\"my \"description \"\"\"schema { query: Query }"),
             Ok(Schema::build()
                 .query("Query")
-                .description("my \"description"))
+                .description("my \"description "))
         )
     }
 
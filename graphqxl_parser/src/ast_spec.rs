@@ -1,18 +1,22 @@
 use crate::ast_import::parse_import;
 use crate::parser::{GraphqxlParser, Rule};
-use crate::utils::{already_defined_error, custom_error, unknown_rule_error};
+use crate::utils::{
+    already_defined_error, for_each_reference, unknown_rule_error, Diagnostic, DiagnosticCode,
+    DiagnosticSpan, FsSourceResolver, MultipleErrors, SourceResolver,
+};
 use crate::{
-    parse_block_def, parse_directive_def, parse_generic_block_def, parse_scalar, parse_schema,
-    parse_union, BlockDef, DirectiveDef, GenericBlockDef, Identifier, OwnedSpan, Scalar, Schema,
-    Union,
+    parse_block_def, parse_directive_def, parse_generic_block_def, parse_generic_union,
+    parse_scalar, parse_schema, parse_union, BlockDef, DirectiveDef, GenericBlockDef, GenericUnion,
+    Identifier, OwnedSpan, Scalar, Schema, Union,
 };
 use pest::iterators::Pair;
 use pest::Parser;
 use std::collections::{HashMap, HashSet};
 use std::error::Error;
-use std::fs;
 use std::path::{Path, PathBuf};
 
+const EXTEND_SEPARATOR: &str = "__extend__";
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum DefType {
     Type(Identifier),
@@ -21,12 +25,34 @@ pub enum DefType {
     GenericInput(Identifier),
     Enum(Identifier),
     Interface(Identifier),
+    GenericInterface(Identifier),
     Scalar(Identifier),
     Union(Identifier),
+    GenericUnion(Identifier),
     Directive(Identifier),
     Schema(String),
 }
 
+impl DefType {
+    /// Key under which the definition is stored in its [Spec] map
+    pub fn key(&self) -> &str {
+        match self {
+            DefType::Type(id)
+            | DefType::GenericType(id)
+            | DefType::Input(id)
+            | DefType::GenericInput(id)
+            | DefType::Enum(id)
+            | DefType::Interface(id)
+            | DefType::GenericInterface(id)
+            | DefType::Scalar(id)
+            | DefType::Union(id)
+            | DefType::GenericUnion(id)
+            | DefType::Directive(id) => id.id.as_str(),
+            DefType::Schema(key) => key.as_str(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct Spec {
     pub types: HashMap<String, BlockDef>,
@@ -35,8 +61,10 @@ pub struct Spec {
     pub generic_inputs: HashMap<String, GenericBlockDef>,
     pub enums: HashMap<String, BlockDef>,
     pub interfaces: HashMap<String, BlockDef>,
+    pub generic_interfaces: HashMap<String, GenericBlockDef>,
     pub scalars: HashMap<String, Scalar>,
     pub unions: HashMap<String, Union>,
+    pub generic_unions: HashMap<String, GenericUnion>,
     pub directives: HashMap<String, DirectiveDef>,
     pub schemas: HashMap<String, Schema>,
     pub order: Vec<DefType>,
@@ -47,113 +75,292 @@ impl Spec {
         Self::default()
     }
 
+    /// Whether `key`, in any of the spec's maps, belongs to an extension instead of
+    /// to the definition itself
+    pub fn is_extension_key(key: &str) -> bool {
+        key.contains(EXTEND_SEPARATOR)
+    }
+
+    /// Name of the definition that `key` belongs to, which is `key` itself unless it
+    /// belongs to an extension
+    pub fn name_of_key(key: &str) -> &str {
+        match key.split_once(EXTEND_SEPARATOR) {
+            Some((name, _)) => name,
+            None => key,
+        }
+    }
+
+    /// Keys of the definition called `name` and of its extensions, in the order in
+    /// which they were declared
+    pub fn keys_of<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.order.iter().filter_map(move |def| {
+            let key = def.key();
+            (Self::name_of_key(key) == name).then_some(key)
+        })
+    }
+
     fn extend_identifier(id: &Identifier) -> Identifier {
         let mut clone = id.clone();
-        clone.id = format!("{}__extend__{}", clone.id, uuid::Uuid::new_v4());
+        clone.id = format!("{}{EXTEND_SEPARATOR}{}", clone.id, uuid::Uuid::new_v4());
         clone
     }
 
-    fn merge(&mut self, other: Spec) -> Result<(), Box<dyn Error>> {
-        for el in other.order.into_iter() {
-            match &el {
-                DefType::Type(name) => {
-                    if self.types.contains_key(&name.id)
-                        || self.generic_types.contains_key(&name.id)
-                    {
-                        return Err(name.span.make_error("Duplicated type"));
-                    }
-                    self.types.insert(
-                        name.id.to_string(),
-                        other.types.get(&name.id).unwrap().clone(),
-                    );
+    /// Names of the types, inputs, enums, interfaces, scalars and unions defined
+    /// in this spec, extensions are not definitions
+    fn defined_names(&self) -> Vec<&Identifier> {
+        self.order
+            .iter()
+            .filter_map(|def| match def {
+                DefType::Type(id)
+                | DefType::GenericType(id)
+                | DefType::Input(id)
+                | DefType::GenericInput(id)
+                | DefType::Enum(id)
+                | DefType::Interface(id)
+                | DefType::GenericInterface(id)
+                | DefType::Scalar(id)
+                | DefType::Union(id)
+                | DefType::GenericUnion(id) => Some(id),
+                DefType::Directive(_) | DefType::Schema(_) => None,
+            })
+            .filter(|id| !Self::is_extension_key(&id.id))
+            .collect()
+    }
+
+    fn defines(&self, name: &str) -> bool {
+        self.defined_names().iter().any(|id| id.id == name)
+    }
+
+    /// Where the definition with this name was first defined, so that errors
+    /// about it being defined again can point there
+    fn definition_span(&self, name: &str) -> Option<&OwnedSpan> {
+        self.order.iter().find_map(|def| match def {
+            DefType::Type(id)
+            | DefType::GenericType(id)
+            | DefType::Input(id)
+            | DefType::GenericInput(id)
+            | DefType::Enum(id)
+            | DefType::Interface(id)
+            | DefType::GenericInterface(id)
+            | DefType::Scalar(id)
+            | DefType::Union(id)
+            | DefType::GenericUnion(id)
+            | DefType::Directive(id) => (id.id == name).then_some(&id.span),
+            DefType::Schema(_) => None,
+        })
+    }
+
+    fn duplicated_error(&self, name: &Identifier, msg: &str) -> Box<Diagnostic> {
+        let mut err = name
+            .span
+            .make_error(DiagnosticCode::DuplicatedDefinition, msg);
+        if let Some(previous) = self.definition_span(&name.id) {
+            err.secondary(DiagnosticSpan::from(previous).label("first defined here"));
+        }
+        err
+    }
+
+    /// Key that `key` has once its definition is renamed, extensions included
+    fn renamed_key(key: &str, renames: &HashMap<String, String>) -> Option<String> {
+        match key.split_once(EXTEND_SEPARATOR) {
+            Some((name, id)) => renames
+                .get(name)
+                .map(|to| format!("{to}{EXTEND_SEPARATOR}{id}")),
+            None => renames.get(key).cloned(),
+        }
+    }
+
+    /// Renames definitions, their extensions and every reference to them in a
+    /// single pass, so that names can be swapped
+    fn rename(&mut self, renames: &HashMap<String, String>) {
+        fn rename_definitions<T>(
+            map: &mut HashMap<String, T>,
+            renames: &HashMap<String, String>,
+            name_of: fn(&mut T) -> &mut Identifier,
+        ) {
+            let keys: Vec<(String, String)> = map
+                .keys()
+                .filter_map(|key| Some((key.clone(), Spec::renamed_key(key, renames)?)))
+                .collect();
+            let mut renamed = Vec::new();
+            for (from, to) in keys {
+                let mut def = map.remove(&from).unwrap();
+                // the names of extensions are references, renamed below
+                if !Spec::is_extension_key(&from) {
+                    name_of(&mut def).id = to.clone();
                 }
-                DefType::GenericType(name) => {
-                    if self.generic_types.contains_key(&name.id)
-                        || self.types.contains_key(&name.id)
-                    {
-                        return Err(name.span.make_error("Duplicated type"));
+                renamed.push((to, def));
+            }
+            map.extend(renamed);
+        }
+        rename_definitions(&mut self.types, renames, |def| &mut def.name);
+        rename_definitions(&mut self.generic_types, renames, |def| &mut def.name);
+        rename_definitions(&mut self.inputs, renames, |def| &mut def.name);
+        rename_definitions(&mut self.generic_inputs, renames, |def| &mut def.name);
+        rename_definitions(&mut self.enums, renames, |def| &mut def.name);
+        rename_definitions(&mut self.interfaces, renames, |def| &mut def.name);
+        rename_definitions(&mut self.generic_interfaces, renames, |def| &mut def.name);
+        rename_definitions(&mut self.scalars, renames, |def| &mut def.name);
+        rename_definitions(&mut self.unions, renames, |def| &mut def.name);
+        rename_definitions(&mut self.generic_unions, renames, |def| &mut def.name);
+        for def in self.order.iter_mut() {
+            match def {
+                DefType::Type(id)
+                | DefType::GenericType(id)
+                | DefType::Input(id)
+                | DefType::GenericInput(id)
+                | DefType::Enum(id)
+                | DefType::Interface(id)
+                | DefType::GenericInterface(id)
+                | DefType::Scalar(id)
+                | DefType::Union(id)
+                | DefType::GenericUnion(id) => {
+                    if let Some(to) = Self::renamed_key(&id.id, renames) {
+                        id.id = to;
                     }
-                    self.generic_types.insert(
-                        name.id.to_string(),
-                        other.generic_types.get(&name.id).unwrap().clone(),
-                    );
                 }
-                DefType::Input(name) => {
-                    if self.inputs.contains_key(&name.id)
-                        && self.generic_inputs.contains_key(&name.id)
-                    {
-                        return Err(name.span.make_error("Duplicated input"));
-                    }
-                    self.inputs.insert(
-                        name.id.to_string(),
-                        other.inputs.get(&name.id).unwrap().clone(),
-                    );
+                DefType::Directive(_) | DefType::Schema(_) => {}
+            }
+        }
+        for_each_reference(self, &mut |id| {
+            if let Some(to) = renames.get(&id.id) {
+                id.id = to.clone();
+            }
+        });
+    }
+
+    fn merge(&mut self, other: Spec) -> Result<(), Box<dyn Error>> {
+        let mut errors = MultipleErrors::new();
+        for el in other.order.iter() {
+            match self.merge_one(&other, el) {
+                Ok(()) => self.order.push(el.clone()),
+                Err(err) => errors.push(err),
+            }
+        }
+        errors.into_result(())
+    }
+
+    fn merge_one(&mut self, other: &Spec, el: &DefType) -> Result<(), Box<dyn Error>> {
+        match el {
+            DefType::Type(name) => {
+                if self.types.contains_key(&name.id) || self.generic_types.contains_key(&name.id) {
+                    return Err(self.duplicated_error(name, "Duplicated type"));
                 }
-                DefType::GenericInput(name) => {
-                    if self.generic_inputs.contains_key(&name.id)
-                        || self.inputs.contains_key(&name.id)
-                    {
-                        return Err(name.span.make_error("Duplicated input"));
-                    }
-                    self.generic_inputs.insert(
-                        name.id.to_string(),
-                        other.generic_inputs.get(&name.id).unwrap().clone(),
-                    );
+                self.types.insert(
+                    name.id.to_string(),
+                    other.types.get(&name.id).unwrap().clone(),
+                );
+            }
+            DefType::GenericType(name) => {
+                if self.generic_types.contains_key(&name.id) || self.types.contains_key(&name.id) {
+                    return Err(self.duplicated_error(name, "Duplicated type"));
                 }
-                DefType::Enum(name) => {
-                    if self.enums.contains_key(&name.id) {
-                        return Err(name.span.make_error("Duplicated enum"));
-                    }
-                    self.enums.insert(
-                        name.id.to_string(),
-                        other.enums.get(&name.id).unwrap().clone(),
-                    );
+                self.generic_types.insert(
+                    name.id.to_string(),
+                    other.generic_types.get(&name.id).unwrap().clone(),
+                );
+            }
+            DefType::Input(name) => {
+                if self.inputs.contains_key(&name.id) && self.generic_inputs.contains_key(&name.id)
+                {
+                    return Err(self.duplicated_error(name, "Duplicated input"));
                 }
-                DefType::Interface(name) => {
-                    if self.interfaces.contains_key(&name.id) {
-                        return Err(name.span.make_error("Duplicated interface"));
-                    }
-                    self.interfaces.insert(
-                        name.id.to_string(),
-                        other.interfaces.get(&name.id).unwrap().clone(),
-                    );
+                self.inputs.insert(
+                    name.id.to_string(),
+                    other.inputs.get(&name.id).unwrap().clone(),
+                );
+            }
+            DefType::GenericInput(name) => {
+                if self.generic_inputs.contains_key(&name.id) || self.inputs.contains_key(&name.id)
+                {
+                    return Err(self.duplicated_error(name, "Duplicated input"));
                 }
-                DefType::Scalar(name) => {
-                    if self.scalars.contains_key(&name.id) {
-                        return Err(name.span.make_error("Duplicated scalar"));
-                    }
-                    self.scalars.insert(
-                        name.id.to_string(),
-                        other.scalars.get(&name.id).unwrap().clone(),
-                    );
+                self.generic_inputs.insert(
+                    name.id.to_string(),
+                    other.generic_inputs.get(&name.id).unwrap().clone(),
+                );
+            }
+            DefType::Enum(name) => {
+                if self.enums.contains_key(&name.id) {
+                    return Err(self.duplicated_error(name, "Duplicated enum"));
                 }
-                DefType::Union(name) => {
-                    if self.unions.contains_key(&name.id) {
-                        return Err(name.span.make_error("Duplicated union"));
-                    }
-                    self.unions.insert(
-                        name.id.to_string(),
-                        other.unions.get(&name.id).unwrap().clone(),
-                    );
+                self.enums.insert(
+                    name.id.to_string(),
+                    other.enums.get(&name.id).unwrap().clone(),
+                );
+            }
+            DefType::Interface(name) => {
+                if self.interfaces.contains_key(&name.id)
+                    || self.generic_interfaces.contains_key(&name.id)
+                {
+                    return Err(self.duplicated_error(name, "Duplicated interface"));
                 }
-                DefType::Directive(name) => {
-                    if self.directives.contains_key(&name.id) {
-                        return Err(name.span.make_error("Duplicated directive"));
-                    }
-                    self.directives.insert(
-                        name.id.to_string(),
-                        other.directives.get(&name.id).unwrap().clone(),
-                    );
+                self.interfaces.insert(
+                    name.id.to_string(),
+                    other.interfaces.get(&name.id).unwrap().clone(),
+                );
+            }
+            DefType::GenericInterface(name) => {
+                if self.generic_interfaces.contains_key(&name.id)
+                    || self.interfaces.contains_key(&name.id)
+                {
+                    return Err(self.duplicated_error(name, "Duplicated interface"));
                 }
-                DefType::Schema(name) => {
-                    if let Some(schema) = self.schemas.get(name) {
-                        return Err(schema.span.make_error("Schema defined multiple times"));
-                    }
-                    self.schemas
-                        .insert(name.clone(), other.schemas.get(name).unwrap().clone());
+                self.generic_interfaces.insert(
+                    name.id.to_string(),
+                    other.generic_interfaces.get(&name.id).unwrap().clone(),
+                );
+            }
+            DefType::Scalar(name) => {
+                if self.scalars.contains_key(&name.id) {
+                    return Err(self.duplicated_error(name, "Duplicated scalar"));
                 }
+                self.scalars.insert(
+                    name.id.to_string(),
+                    other.scalars.get(&name.id).unwrap().clone(),
+                );
+            }
+            DefType::Union(name) => {
+                if self.unions.contains_key(&name.id) || self.generic_unions.contains_key(&name.id)
+                {
+                    return Err(self.duplicated_error(name, "Duplicated union"));
+                }
+                self.unions.insert(
+                    name.id.to_string(),
+                    other.unions.get(&name.id).unwrap().clone(),
+                );
+            }
+            DefType::GenericUnion(name) => {
+                if self.generic_unions.contains_key(&name.id) || self.unions.contains_key(&name.id)
+                {
+                    return Err(self.duplicated_error(name, "Duplicated union"));
+                }
+                self.generic_unions.insert(
+                    name.id.to_string(),
+                    other.generic_unions.get(&name.id).unwrap().clone(),
+                );
+            }
+            DefType::Directive(name) => {
+                if self.directives.contains_key(&name.id) {
+                    return Err(self.duplicated_error(name, "Duplicated directive"));
+                }
+                self.directives.insert(
+                    name.id.to_string(),
+                    other.directives.get(&name.id).unwrap().clone(),
+                );
+            }
+            DefType::Schema(name) => {
+                if let Some(schema) = self.schemas.get(name) {
+                    let mut err = other.schemas.get(name).unwrap().span.make_error(
+                        DiagnosticCode::DuplicatedDefinition,
+                        "Schema defined multiple times",
+                    );
+                    err.secondary(DiagnosticSpan::from(&schema.span).label("first defined here"));
+                    return Err(err);
+                }
+                self.schemas
+                    .insert(name.clone(), other.schemas.get(name).unwrap().clone());
             }
-            self.order.push(el);
         }
         Ok(())
     }
@@ -163,8 +370,13 @@ impl Spec {
             Rule::schema_def => {
                 let schema = parse_schema(pair.clone(), file)?;
                 let id = "schema".to_string();
-                if self.schemas.contains_key(&id) {
-                    Err(Box::new(custom_error(pair, "schema is already defined")))
+                if let Some(previous) = self.schemas.get(&id) {
+                    let mut err = OwnedSpan::from(pair.as_span(), file).make_error(
+                        DiagnosticCode::DuplicatedDefinition,
+                        "schema is already defined",
+                    );
+                    err.secondary(DiagnosticSpan::from(&previous.span).label("first defined here"));
+                    Err(err)
                 } else {
                     self.schemas.insert(id.clone(), schema);
                     self.order.push(DefType::Schema(id));
@@ -182,7 +394,13 @@ impl Spec {
                 let block_def = parse_block_def(pair.clone(), file)?;
                 let id = block_def.name.clone();
                 if self.types.contains_key(&id.id) || self.generic_types.contains_key(&id.id) {
-                    Err(Box::new(already_defined_error(pair, "type", &id.id)))
+                    Err(already_defined_error(
+                        pair,
+                        file,
+                        "type",
+                        &id.id,
+                        self.definition_span(&id.id),
+                    ))
                 } else {
                     self.types.insert(id.id.clone(), block_def);
                     self.order.push(DefType::Type(id));
@@ -200,7 +418,13 @@ impl Spec {
                 let generic_block_def = parse_generic_block_def(pair.clone(), file)?;
                 let id = generic_block_def.name.clone();
                 if self.generic_types.contains_key(&id.id) || self.types.contains_key(&id.id) {
-                    Err(Box::new(already_defined_error(pair, "type", &id.id)))
+                    Err(already_defined_error(
+                        pair,
+                        file,
+                        "type",
+                        &id.id,
+                        self.definition_span(&id.id),
+                    ))
                 } else {
                     self.generic_types.insert(id.id.clone(), generic_block_def);
                     self.order.push(DefType::GenericType(id));
@@ -211,7 +435,13 @@ impl Spec {
                 let block_def = parse_block_def(pair.clone(), file)?;
                 let id = block_def.name.clone();
                 if self.inputs.contains_key(&id.id) || self.generic_inputs.contains_key(&id.id) {
-                    Err(Box::new(already_defined_error(pair, "input", &id.id)))
+                    Err(already_defined_error(
+                        pair,
+                        file,
+                        "input",
+                        &id.id,
+                        self.definition_span(&id.id),
+                    ))
                 } else {
                     self.inputs.insert(id.id.clone(), block_def);
                     self.order.push(DefType::Input(id));
@@ -229,7 +459,13 @@ impl Spec {
                 let generic_block_def = parse_generic_block_def(pair.clone(), file)?;
                 let id = generic_block_def.name.clone();
                 if self.generic_inputs.contains_key(&id.id) || self.inputs.contains_key(&id.id) {
-                    Err(Box::new(already_defined_error(pair, "input", &id.id)))
+                    Err(already_defined_error(
+                        pair,
+                        file,
+                        "input",
+                        &id.id,
+                        self.definition_span(&id.id),
+                    ))
                 } else {
                     self.generic_inputs.insert(id.id.clone(), generic_block_def);
                     self.order.push(DefType::GenericInput(id));
@@ -240,7 +476,13 @@ impl Spec {
                 let block_def = parse_block_def(pair.clone(), file)?;
                 let id = block_def.name.clone();
                 if self.enums.contains_key(&id.id) {
-                    Err(Box::new(already_defined_error(pair, "enum", &id.id)))
+                    Err(already_defined_error(
+                        pair,
+                        file,
+                        "enum",
+                        &id.id,
+                        self.definition_span(&id.id),
+                    ))
                 } else {
                     self.enums.insert(id.id.clone(), block_def);
                     self.order.push(DefType::Enum(id));
@@ -257,8 +499,16 @@ impl Spec {
             Rule::interface_def => {
                 let block_def = parse_block_def(pair.clone(), file)?;
                 let id = block_def.name.clone();
-                if self.interfaces.contains_key(&id.id) {
-                    Err(Box::new(already_defined_error(pair, "interface", &id.id)))
+                if self.interfaces.contains_key(&id.id)
+                    || self.generic_interfaces.contains_key(&id.id)
+                {
+                    Err(already_defined_error(
+                        pair,
+                        file,
+                        "interface",
+                        &id.id,
+                        self.definition_span(&id.id),
+                    ))
                 } else {
                     self.interfaces.insert(id.id.clone(), block_def);
                     self.order.push(DefType::Interface(id));
@@ -272,11 +522,37 @@ impl Spec {
                 self.order.push(DefType::Interface(id));
                 Ok(())
             }
+            Rule::generic_interface_def => {
+                let generic_block_def = parse_generic_block_def(pair.clone(), file)?;
+                let id = generic_block_def.name.clone();
+                if self.generic_interfaces.contains_key(&id.id)
+                    || self.interfaces.contains_key(&id.id)
+                {
+                    Err(already_defined_error(
+                        pair,
+                        file,
+                        "interface",
+                        &id.id,
+                        self.definition_span(&id.id),
+                    ))
+                } else {
+                    self.generic_interfaces
+                        .insert(id.id.clone(), generic_block_def);
+                    self.order.push(DefType::GenericInterface(id));
+                    Ok(())
+                }
+            }
             Rule::scalar_def => {
                 let scalar = parse_scalar(pair.clone(), file)?;
                 let id = scalar.name.clone();
                 if self.scalars.contains_key(&id.id) {
-                    Err(Box::new(already_defined_error(pair, "scalar", &id.id)))
+                    Err(already_defined_error(
+                        pair,
+                        file,
+                        "scalar",
+                        &id.id,
+                        self.definition_span(&id.id),
+                    ))
                 } else {
                     self.scalars.insert(id.id.clone(), scalar);
                     self.order.push(DefType::Scalar(id));
@@ -293,8 +569,14 @@ impl Spec {
             Rule::union_def => {
                 let union = parse_union(pair.clone(), file)?;
                 let id = union.name.clone();
-                if self.unions.contains_key(&id.id) {
-                    Err(Box::new(already_defined_error(pair, "union", &id.id)))
+                if self.unions.contains_key(&id.id) || self.generic_unions.contains_key(&id.id) {
+                    Err(already_defined_error(
+                        pair,
+                        file,
+                        "union",
+                        &id.id,
+                        self.definition_span(&id.id),
+                    ))
                 } else {
                     self.unions.insert(id.id.clone(), union);
                     self.order.push(DefType::Union(id));
@@ -308,11 +590,34 @@ impl Spec {
                 self.order.push(DefType::Union(id));
                 Ok(())
             }
+            Rule::generic_union_def => {
+                let generic_union = parse_generic_union(pair.clone(), file)?;
+                let id = generic_union.name.clone();
+                if self.generic_unions.contains_key(&id.id) || self.unions.contains_key(&id.id) {
+                    Err(already_defined_error(
+                        pair,
+                        file,
+                        "union",
+                        &id.id,
+                        self.definition_span(&id.id),
+                    ))
+                } else {
+                    self.generic_unions.insert(id.id.clone(), generic_union);
+                    self.order.push(DefType::GenericUnion(id));
+                    Ok(())
+                }
+            }
             Rule::directive_def => {
                 let directive = parse_directive_def(pair.clone(), file)?;
                 let id = directive.name.clone();
                 if self.directives.contains_key(&id.id) {
-                    Err(Box::new(already_defined_error(pair, "directive", &id.id)))
+                    Err(already_defined_error(
+                        pair,
+                        file,
+                        "directive",
+                        &id.id,
+                        self.definition_span(&id.id),
+                    ))
                 } else {
                     self.directives.insert(id.id.clone(), directive);
                     self.order.push(DefType::Directive(id));
@@ -343,7 +648,10 @@ fn check_import_loop(import_stack: &Vec<PathBuf>, span: &OwnedSpan) -> Result<()
                     msg += &import_string;
                 }
             }
-            return Err(span.make_error(&format!("cyclical import {}", msg)));
+            return Err(span.make_error(
+                DiagnosticCode::CyclicalImport,
+                &format!("cyclical import {}", msg),
+            ));
         } else {
             seen.insert(import_string);
         }
@@ -351,63 +659,315 @@ fn check_import_loop(import_stack: &Vec<PathBuf>, span: &OwnedSpan) -> Result<()
     Ok(())
 }
 
+/// Names visible from a file mapped to the unique name of the definition they
+/// refer to, which is different if they were imported with an alias or if other
+/// files define the same name
+type Scope = HashMap<String, String>;
+
+/// Separates the name of a definition from the number that makes it unique while
+/// the files are parsed, if other files define the same name
+const UNIQUE_SEPARATOR: &str = "__defined__";
+
+struct Definition {
+    /// The unique name used for it until every file is parsed
+    unique: String,
+    name: String,
+    file: PathBuf,
+    /// The names it was imported with by the files that aliased it
+    aliases: Vec<String>,
+}
+
+#[derive(Default)]
+struct ImportState {
+    /// Everything that is visible from each already parsed file can be imported from it
+    exports: HashMap<PathBuf, Scope>,
+    /// References to names that were not imported in files that use selective imports
+    hidden_references: Vec<Identifier>,
+    /// Every definition in the order in which they were parsed
+    definitions: Vec<Definition>,
+}
+
+impl ImportState {
+    /// Registers the definition called `name` in `file`, which keeps its name until
+    /// every file is parsed unless another file already defines it
+    fn define(&mut self, name: &str, file: &Path) -> String {
+        let mut unique = name.to_string();
+        let mut count = 0;
+        while self.definitions.iter().any(|def| def.unique == unique) {
+            count += 1;
+            unique = format!("{name}{UNIQUE_SEPARATOR}{count}");
+        }
+        self.definitions.push(Definition {
+            unique: unique.clone(),
+            name: name.to_string(),
+            file: file.to_path_buf(),
+            aliases: vec![],
+        });
+        unique
+    }
+
+    fn alias(&mut self, unique: &str, alias: &str) {
+        if let Some(def) = self.definitions.iter_mut().find(|def| def.unique == unique) {
+            if !def.aliases.iter().any(|existing| existing == alias) {
+                def.aliases.push(alias.to_string());
+            }
+        }
+    }
+
+    /// The names of the definitions in the resulting spec. A definition keeps its name
+    /// unless other files define the same one. In that case, it takes the first alias it
+    /// was imported with, or, if it has none, the first one keeps the name and the others
+    /// get the name of their file appended, like `UserAccounts` for `User` in `accounts`.
+    fn output_names(&self) -> HashMap<String, String> {
+        let count = |name: &str| self.definitions.iter().filter(|d| d.name == name).count();
+        let mut taken: HashSet<String> = self
+            .definitions
+            .iter()
+            .filter(|def| count(&def.name) == 1)
+            .map(|def| def.name.clone())
+            .collect();
+        let mut renames = HashMap::new();
+        for def in self.definitions.iter().filter(|def| count(&def.name) > 1) {
+            let namespaced = format!("{}{}", def.name, file_namespace(&def.file));
+            let mut candidates: Vec<String> = def.aliases.clone();
+            if def.aliases.is_empty() {
+                candidates.push(def.name.clone());
+            }
+            candidates.push(namespaced.clone());
+            let output = candidates
+                .into_iter()
+                .find(|candidate| !taken.contains(candidate))
+                .unwrap_or_else(|| {
+                    (2..)
+                        .map(|n| format!("{namespaced}{n}"))
+                        .find(|candidate| !taken.contains(candidate))
+                        .unwrap()
+                });
+            taken.insert(output.clone());
+            if output != def.unique {
+                renames.insert(def.unique.clone(), output);
+            }
+        }
+        renames
+    }
+}
+
+/// The name of `file` in PascalCase, like `UserAccounts` for `user-accounts.graphqxl`
+fn file_namespace(file: &Path) -> String {
+    let stem = file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    stem.split(|c: char| !c.is_ascii_alphanumeric())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn add_to_scope(
+    scope: &mut Scope,
+    name: &str,
+    output_name: &str,
+    span: &OwnedSpan,
+) -> Result<(), Box<dyn Error>> {
+    match scope.get(name) {
+        Some(existing) if existing != output_name => Err(span.make_error(
+            DiagnosticCode::ImportConflict,
+            &format!("{name} is already imported as a different definition"),
+        )),
+        _ => {
+            scope.insert(name.to_string(), output_name.to_string());
+            Ok(())
+        }
+    }
+}
+
+fn import_spec(
+    child: Pair<Rule>,
+    abs_path: &Path,
+    resolver: &dyn SourceResolver,
+    import_stack: &[PathBuf],
+    state: &mut ImportState,
+    spec: &mut Spec,
+    scope: &mut Scope,
+) -> Result<(), Box<dyn Error>> {
+    let import = parse_import(child, abs_path.to_str().unwrap())?;
+    let file_name = if import.file_name.ends_with(".graphqxl") {
+        import.file_name.clone()
+    } else {
+        import.file_name.clone() + ".graphqxl"
+    };
+    let file_dir = abs_path.parent().unwrap();
+    let import_path = Path::new(file_dir).join(&file_name);
+    if !resolver.exists(&import_path) {
+        return Err(import.span.make_error(
+            DiagnosticCode::ImportNotFound,
+            &format!("file {:?} does not exist", import_path),
+        ));
+    }
+    let mut stack = import_stack.to_vec();
+    stack.push(import_path.clone());
+    check_import_loop(&stack, &import.span)?;
+    let imported_spec = private_parse_spec(&import_path, resolver, stack, state)?;
+    let import_abs_path = resolver.canonicalize(&import_path)?;
+    let exports = state
+        .exports
+        .get(&import_abs_path)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut errors = MultipleErrors::new();
+    if import.items.is_empty() {
+        for (name, unique) in exports.iter() {
+            if let Err(err) = add_to_scope(scope, name, unique, &import.span) {
+                errors.push(err);
+            }
+        }
+    } else {
+        for item in import.items.iter() {
+            let Some(unique) = exports.get(&item.name.id) else {
+                errors.push(item.name.span.make_error(
+                    DiagnosticCode::UndefinedImport,
+                    &format!(
+                        "{} is not defined in \"{}\"",
+                        item.name.id, import.file_name
+                    ),
+                ));
+                continue;
+            };
+            // the alias is the name that it has in this file only
+            let name = match &item.alias {
+                Some(alias) => {
+                    state.alias(unique, &alias.id);
+                    alias
+                }
+                None => &item.name,
+            };
+            if let Err(err) = add_to_scope(scope, &name.id, unique, &item.span) {
+                errors.push(err);
+            }
+        }
+    }
+    if let Err(err) = spec.merge(imported_spec) {
+        errors.push(err);
+    }
+    errors.into_result(())
+}
+
 fn private_parse_spec<P: AsRef<Path>>(
     path: P,
+    resolver: &dyn SourceResolver,
     import_stack: Vec<PathBuf>,
-    already_imported: &mut HashSet<PathBuf>,
+    state: &mut ImportState,
 ) -> Result<Spec, Box<dyn Error>> {
-    let abs_path = fs::canonicalize(path)?;
+    let abs_path = resolver.canonicalize(path.as_ref())?;
     let file = abs_path.to_str().unwrap();
 
     let mut spec = Spec::new();
-    if already_imported.contains(&abs_path) {
+    if state.exports.contains_key(&abs_path) {
         return Ok(spec);
     }
-    let content = fs::read_to_string(&abs_path)?;
-    let mut pairs = GraphqxlParser::parse(Rule::spec, &content)?;
+    let content = resolver.read_to_string(&abs_path)?;
+    let mut pairs = match GraphqxlParser::parse(Rule::spec, &content) {
+        Ok(pairs) => pairs,
+        Err(err) => {
+            // nothing can be imported from a file with syntax errors, and it
+            // should not be reported again if other files import it too
+            state.exports.insert(abs_path.clone(), Scope::new());
+            return Err(Box::new(err.with_path(file)));
+        }
+    };
     let pair = pairs.next().unwrap();
     match pair.as_rule() {
         Rule::spec => {
+            let mut errors = MultipleErrors::new();
+            let mut local = Spec::new();
+            let mut scope = Scope::new();
+            let mut selective = false;
             for child in pair.into_inner() {
                 if let Rule::EOI = &child.as_rule() {
                     // nothing to do here
                 } else if let Rule::import = &child.as_rule() {
-                    let import = parse_import(child.clone(), file)?;
-                    let file_name = if import.file_name.ends_with(".graphqxl") {
-                        import.file_name
-                    } else {
-                        import.file_name + ".graphqxl"
-                    };
-                    let file_dir = abs_path.parent().unwrap();
-                    let import_path = Path::new(file_dir).join(&file_name);
-                    if !import_path.exists() {
-                        return Err(import.span.make_error(
-                            format!("file {:?} does not exist", import_path).as_str(),
-                        ));
+                    selective |= child
+                        .clone()
+                        .into_inner()
+                        .any(|pair| pair.as_rule() == Rule::import_selection);
+                    if let Err(err) = import_spec(
+                        child,
+                        &abs_path,
+                        resolver,
+                        &import_stack,
+                        state,
+                        &mut spec,
+                        &mut scope,
+                    ) {
+                        errors.push(err);
                     }
-                    let mut stack = import_stack.clone();
-                    stack.push(import_path.clone());
-                    check_import_loop(&stack, &import.span)?;
-                    let imported_spec = private_parse_spec(import_path, stack, already_imported)?;
-                    spec.merge(imported_spec)?;
-                } else {
-                    spec.add(child, file)?;
+                } else if let Err(err) = local.add(child, file) {
+                    errors.push(err);
+                }
+            }
+
+            for name in local.defined_names() {
+                let unique = state.define(&name.id, &abs_path);
+                if let Err(err) = add_to_scope(&mut scope, &name.id, &unique, &name.span) {
+                    errors.push(err);
+                }
+            }
+            let renames: HashMap<String, String> = scope
+                .iter()
+                .filter(|(name, unique)| name != unique)
+                .map(|(name, unique)| (name.clone(), unique.clone()))
+                .collect();
+            for_each_reference(&mut local, &mut |id| {
+                if selective && !scope.contains_key(&id.id) {
+                    state.hidden_references.push(id.clone());
                 }
+            });
+            local.rename(&renames);
+            if let Err(err) = spec.merge(local) {
+                errors.push(err);
             }
-            already_imported.insert(abs_path.clone());
-            Ok(spec)
+            state.exports.insert(abs_path.clone(), scope);
+            errors.into_result(spec)
         }
         _unknown => Err(Box::new(unknown_rule_error(pair, "spec"))),
     }
 }
 
 pub fn parse_spec<P: AsRef<Path>>(path: P) -> Result<Spec, Box<dyn Error>> {
-    private_parse_spec(path, Vec::new(), &mut HashSet::new())
+    parse_spec_with_resolver(path, &FsSourceResolver)
+}
+
+pub fn parse_spec_with_resolver<P: AsRef<Path>>(
+    path: P,
+    resolver: &dyn SourceResolver,
+) -> Result<Spec, Box<dyn Error>> {
+    let mut state = ImportState::default();
+    let spec = private_parse_spec(path, resolver, Vec::new(), &mut state)?;
+    let mut errors = MultipleErrors::new();
+    for reference in state.hidden_references.iter() {
+        if spec.defines(&reference.id) {
+            errors.push(reference.span.make_error(
+                DiagnosticCode::NotImported,
+                &format!("{} is not imported in this file", reference.id),
+            ));
+        }
+    }
+    let mut spec = errors.into_result(spec)?;
+    spec.rename(&state.output_names());
+    Ok(spec)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{BlockEntry, InMemorySourceResolver};
 
     #[test]
     fn test_parses_spec_1() {
@@ -423,6 +983,198 @@ mod tests {
         assert!(err.to_string().contains("cyclical"))
     }
 
+    #[test]
+    fn test_parses_spec_from_memory() {
+        let resolver = InMemorySourceResolver::new()
+            .source("dir/main.graphqxl", "import \"../other\"\ntype A { ...B }")
+            .source("other.graphqxl", "type B { b: String }");
+        let spec = parse_spec_with_resolver("dir/main.graphqxl", &resolver).unwrap();
+        assert!(spec.types.contains_key("A"));
+        assert!(spec.types.contains_key("B"));
+    }
+
+    #[test]
+    fn test_finds_the_keys_of_a_definition_and_its_extensions() {
+        let resolver = InMemorySourceResolver::new().source(
+            "main.graphqxl",
+            "type A { a: Int } extend type A { b: Int } type B { c: Int } extend type A { d: Int }",
+        );
+        let spec = parse_spec_with_resolver("main.graphqxl", &resolver).unwrap();
+        let keys: Vec<&str> = spec.keys_of("A").collect();
+        assert_eq!(keys.len(), 3);
+        assert_eq!(keys[0], "A");
+        assert!(keys[1..].iter().all(|key| Spec::is_extension_key(key)));
+        assert!(keys.iter().all(|key| Spec::name_of_key(key) == "A"));
+        assert_eq!(spec.types[keys[2]].entries.len(), 1);
+    }
+
+    #[test]
+    fn test_handles_missing_in_memory_imports() {
+        let resolver =
+            InMemorySourceResolver::new().source("main.graphqxl", "import \"other\"\ntype A {}");
+        let err = parse_spec_with_resolver("main.graphqxl", &resolver).unwrap_err();
+        assert!(err.to_string().contains("does not exist"))
+    }
+
+    #[test]
+    fn test_selective_import_hides_other_names() {
+        let resolver = InMemorySourceResolver::new()
+            .source(
+                "main.graphqxl",
+                "import { User } from \"users\"\ntype A { user: User }\ntype B { team: Team }",
+            )
+            .source(
+                "users.graphqxl",
+                "type User { a: Int }\ntype Team { a: Int }",
+            );
+        let err = parse_spec_with_resolver("main.graphqxl", &resolver).unwrap_err();
+        let err = err.to_string();
+        assert!(err.contains("Team is not imported"));
+        assert!(err.contains("/main.graphqxl:3:"));
+    }
+
+    #[test]
+    fn test_selective_import_of_undefined_name() {
+        let resolver = InMemorySourceResolver::new()
+            .source("main.graphqxl", "import { Missing } from \"users\"")
+            .source("users.graphqxl", "type User { a: Int }");
+        let err = parse_spec_with_resolver("main.graphqxl", &resolver).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Missing is not defined in \"users\""));
+    }
+
+    #[test]
+    fn test_aliased_imports_avoid_collisions() {
+        let resolver = InMemorySourceResolver::new()
+            .source(
+                "main.graphqxl",
+                "import { User as AccountUser } from \"accounts\"\nimport { User } from \"users\"\ntype A { a: AccountUser, b: User }",
+            )
+            .source("accounts.graphqxl", "type User { id: Int }\ntype Account { user: User }\nextend type User { b: Int }")
+            .source("users.graphqxl", "type User { name: String }");
+        let spec = parse_spec_with_resolver("main.graphqxl", &resolver).unwrap();
+        assert_eq!(
+            spec.types.get("AccountUser").unwrap().name.id,
+            "AccountUser"
+        );
+        assert!(spec.types.contains_key("User"));
+        let account = spec.types.get("Account").unwrap();
+        let BlockEntry::Field(field) = &account.entries[0] else {
+            panic!()
+        };
+        assert_eq!(
+            field
+                .value_type
+                .as_ref()
+                .unwrap()
+                .retrieve_basic_type()
+                .to_string(),
+            "AccountUser"
+        );
+        let extension = spec.types.values().find(|t| t.extend).unwrap();
+        assert_eq!(extension.name.id, "AccountUser");
+    }
+
+    fn field_type(spec: &Spec, type_name: &str) -> String {
+        let BlockEntry::Field(field) = &spec.types[type_name].entries[0] else {
+            panic!("expected a field")
+        };
+        field
+            .value_type
+            .as_ref()
+            .unwrap()
+            .retrieve_basic_type()
+            .to_string()
+    }
+
+    #[test]
+    fn test_aliases_are_local_to_the_importing_file() {
+        let resolver = InMemorySourceResolver::new()
+            .source(
+                "main.graphqxl",
+                "import { User as AccountUser } from \"accounts\"\nimport \"other\"\ntype A { user: AccountUser }",
+            )
+            .source("accounts.graphqxl", "type User { id: Int }")
+            .source(
+                "other.graphqxl",
+                "import { User } from \"accounts\"\ntype Other { user: User }",
+            );
+        let spec = parse_spec_with_resolver("main.graphqxl", &resolver).unwrap();
+        // nothing else is called User, so there is no need to rename it
+        assert!(spec.types.contains_key("User"));
+        assert!(!spec.types.contains_key("AccountUser"));
+        assert_eq!(field_type(&spec, "A"), "User");
+        assert_eq!(field_type(&spec, "Other"), "User");
+    }
+
+    #[test]
+    fn test_can_alias_an_already_imported_file() {
+        let resolver = InMemorySourceResolver::new()
+            .source(
+                "main.graphqxl",
+                "import \"billing\"\nimport { User as AccountUser } from \"accounts\"\ntype A { user: AccountUser }",
+            )
+            .source("billing.graphqxl", "import \"accounts\"")
+            .source("accounts.graphqxl", "type User { id: Int }");
+        let spec = parse_spec_with_resolver("main.graphqxl", &resolver).unwrap();
+        assert_eq!(field_type(&spec, "A"), "User");
+    }
+
+    #[test]
+    fn test_unselected_definitions_do_not_collide() {
+        let resolver = InMemorySourceResolver::new()
+            .source(
+                "main.graphqxl",
+                "import { A } from \"a\"\nimport { B } from \"b\"\ntype Query { a: A b: B }",
+            )
+            .source(
+                "a.graphqxl",
+                "type A { foo: Foo }\ntype Foo { a: Int }\nextend type Foo { c: Int }",
+            )
+            .source("b.graphqxl", "type B { foo: Foo }\ntype Foo { b: Int }");
+        let spec = parse_spec_with_resolver("main.graphqxl", &resolver).unwrap();
+        assert_eq!(field_type(&spec, "A"), "Foo");
+        assert_eq!(field_type(&spec, "B"), "FooB");
+        assert_eq!(spec.types["FooB"].name.id, "FooB");
+        assert_eq!(spec.keys_of("Foo").count(), 2);
+        assert_eq!(spec.keys_of("FooB").count(), 1);
+    }
+
+    #[test]
+    fn test_visible_definitions_with_the_same_name_collide() {
+        let resolver = InMemorySourceResolver::new()
+            .source("main.graphqxl", "import \"a\"\nimport \"b\"")
+            .source("a.graphqxl", "type Foo { a: Int }")
+            .source("b.graphqxl", "type Foo { b: Int }");
+        let err = parse_spec_with_resolver("main.graphqxl", &resolver).unwrap_err();
+        let diagnostic = Diagnostic::from_error(MultipleErrors::flatten(err)[0].as_ref());
+        assert_eq!(diagnostic.code, DiagnosticCode::ImportConflict);
+        assert_eq!(
+            diagnostic.message,
+            "Foo is already imported as a different definition"
+        );
+    }
+
+    #[test]
+    fn test_reports_all_errors() {
+        let resolver = InMemorySourceResolver::new()
+            .source(
+                "main.graphqxl",
+                "import \"missing\"\nimport \"broken\"\ntype A { a: Int }\ntype A { a: Int }",
+            )
+            .source("broken.graphqxl", "type B {");
+        let err = parse_spec_with_resolver("main.graphqxl", &resolver).unwrap_err();
+        let errors: Vec<String> = MultipleErrors::flatten(err)
+            .iter()
+            .map(|err| err.to_string())
+            .collect();
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].contains("does not exist"));
+        assert!(errors[1].contains("broken.graphqxl"));
+        assert!(errors[2].contains("already defined"));
+    }
+
     #[test]
     fn test_does_not_duplicate_imports() {
         let spec_or_err = parse_spec("test_graphqxl_files/no_duplicated1.graphqxl");
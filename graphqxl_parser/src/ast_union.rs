@@ -2,16 +2,23 @@ use crate::ast_description::{parse_description_and_continue, DescriptionAndNext}
 use crate::ast_identifier::{parse_identifier, Identifier};
 use crate::parser::{Rule, RuleError};
 use crate::utils::{unknown_rule_error, OwnedSpan};
-use crate::{parse_directive, Directive};
+use crate::{parse_directive, parse_generic, Directive, Generic};
 use pest::iterators::Pair;
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnionMember {
+    Type(Identifier),
+    Spread(Identifier),
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct Union {
     pub extend: bool,
     pub span: OwnedSpan,
     pub name: Identifier,
+    pub generic: Option<Generic>,
     pub description: String,
-    pub types: Vec<Identifier>,
+    pub members: Vec<UnionMember>,
     pub directives: Vec<Directive>,
 }
 
@@ -29,10 +36,25 @@ impl Union {
     }
 
     pub fn type_(&mut self, type_: &str) -> Self {
-        self.types.push(Identifier::from(type_));
+        self.members
+            .push(UnionMember::Type(Identifier::from(type_)));
         self.clone()
     }
 
+    pub fn spread(&mut self, union: &str) -> Self {
+        self.members
+            .push(UnionMember::Spread(Identifier::from(union)));
+        self.clone()
+    }
+
+    /// Members that are not spreads, which are all of them once transpiled
+    pub fn types(&self) -> impl Iterator<Item = &Identifier> {
+        self.members.iter().filter_map(|member| match member {
+            UnionMember::Type(identifier) => Some(identifier),
+            UnionMember::Spread(_) => None,
+        })
+    }
+
     pub fn directive(&mut self, directive: Directive) -> Self {
         self.directives.push(directive);
         self.clone()
@@ -42,30 +64,40 @@ impl Union {
         self.extend = true;
         self.clone()
     }
+
+    pub fn generic(&mut self, generic: Generic) -> Self {
+        self.generic = Some(generic);
+        self.clone()
+    }
 }
 
 fn _parse_union(pair: Pair<Rule>, file: &str, extends: bool) -> Result<Union, Box<RuleError>> {
     let span = OwnedSpan::from(pair.as_span(), file);
     let mut childs = pair.into_inner();
-    // [description?, identifier, ...types]
-    let DescriptionAndNext(description, next) = parse_description_and_continue(&mut childs, file);
+    // [description?, identifier, ...members]
+    let DescriptionAndNext(description, next) = parse_description_and_continue(&mut childs, file)?;
     let name = parse_identifier(next.unwrap(), file)?;
-    let mut types = Vec::new();
+    let mut generic = None;
+    let mut members = Vec::new();
     let mut directives = Vec::new();
     for child in childs {
-        if let Rule::directive = child.as_rule() {
-            directives.push(parse_directive(child, file)?);
-        } else {
-            let name = parse_identifier(child.clone(), file)?;
-            types.push(name);
+        match child.as_rule() {
+            Rule::generic => generic = Some(parse_generic(child, file)?),
+            Rule::directive => directives.push(parse_directive(child, file)?),
+            Rule::union_spread => members.push(UnionMember::Spread(parse_identifier(
+                child.into_inner().next().unwrap(),
+                file,
+            )?)),
+            _ => members.push(UnionMember::Type(parse_identifier(child, file)?)),
         }
     }
     Ok(Union {
         extend: extends,
         span,
         name,
+        generic,
         description,
-        types,
+        members,
         directives,
     })
 }
@@ -91,7 +123,7 @@ mod tests {
     #[test]
     fn test_accepts_description() {
         assert_eq!(
-            parse_input("\"\"\" my description \"\"\"union MyUnion = Type1"),
+            parse_input("\"\"\"my description\"\"\"union MyUnion = Type1"),
             Ok(Union::build("MyUnion")
                 .description("my description")
                 .type_("Type1"))
@@ -136,6 +168,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parses_generic_union() {
+        assert_eq!(
+            parse_input("union Result<T> = T | Error"),
+            Ok(Union::build("Result")
+                .generic(Generic::from("T"))
+                .type_("T")
+                .type_("Error"))
+        );
+    }
+
+    #[test]
+    fn test_parses_spreads() {
+        assert_eq!(
+            parse_input("union SearchResult = ...MediaResult | User"),
+            Ok(Union::build("SearchResult")
+                .spread("MediaResult")
+                .type_("User"))
+        );
+    }
+
+    #[test]
+    fn test_parses_spreads_in_extensions() {
+        assert_eq!(
+            parse_input("extend union SearchResult = User | ...MediaResult"),
+            Ok(Union::build("SearchResult")
+                .type_("User")
+                .spread("MediaResult")
+                .extend())
+        );
+    }
+
     #[test]
     fn test_invalid_input_not_an_equal() {
         parse_input("union UnionType: Type1 | Type2").unwrap_err();
@@ -1,27 +1,37 @@
 use crate::parser::{Rule, RuleError};
-use crate::utils::unknown_rule_error;
+use crate::utils::{custom_error, unknown_rule_error};
 use pest::iterators::Pair;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ValueBasicData {
     Int(i64),
-    Float(f64),
+    /// The float as it is written in the source, so that it is generated exactly the same
+    Float(String),
     String(String),
     Boolean(bool),
+    Null,
+    Enum(String),
+    Variable(String),
 }
 
 pub(crate) fn _parse_basic_data(pair: Pair<Rule>) -> Result<ValueBasicData, Box<RuleError>> {
     match pair.as_rule() {
-        Rule::int_data => Ok(ValueBasicData::Int(pair.as_str().parse::<i64>().unwrap())),
-        Rule::float_data => Ok(ValueBasicData::Float(pair.as_str().parse::<f64>().unwrap())),
+        Rule::int_data => match pair.as_str().parse::<i64>() {
+            Ok(int) => Ok(ValueBasicData::Int(int)),
+            Err(_) => Err(custom_error(pair, "integer out of range")),
+        },
+        Rule::float_data => Ok(ValueBasicData::Float(pair.as_str().to_string())),
         Rule::string_data => Ok(ValueBasicData::String({
             let str = pair.as_str();
             str[1..str.len() - 1].to_string()
         })),
         Rule::boolean_data => Ok(ValueBasicData::Boolean(pair.as_str() == "true")),
+        Rule::null_data => Ok(ValueBasicData::Null),
+        Rule::enum_data => Ok(ValueBasicData::Enum(pair.as_str().to_string())),
+        Rule::variable_data => Ok(ValueBasicData::Variable(pair.as_str()[1..].to_string())),
         _unknown => Err(unknown_rule_error(
             pair,
-            "int_data, float_data, string_data, boolean_data",
+            "int_data, float_data, string_data, boolean_data, null_data, enum_data, variable_data",
         )),
     }
 }
@@ -59,7 +69,35 @@ mod tests {
     #[test]
     fn test_float() {
         let float = parse_input("12.34").unwrap();
-        assert_eq!(float, ValueBasicData::Float(12.34))
+        assert_eq!(float, ValueBasicData::Float("12.34".to_string()))
+    }
+
+    #[test]
+    fn test_negative_numbers() {
+        assert_eq!(parse_input("-12").unwrap(), ValueBasicData::Int(-12));
+        assert_eq!(
+            parse_input("-0.5").unwrap(),
+            ValueBasicData::Float("-0.5".to_string())
+        );
+    }
+
+    #[test]
+    fn test_exponent_floats() {
+        for float in ["1e10", "1E-3", "-1.5e+7", "0.25e2"] {
+            assert_eq!(
+                parse_input(float).unwrap(),
+                ValueBasicData::Float(float.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_numbers() {
+        parse_input("01").unwrap_err();
+        parse_input("1e").unwrap_err();
+        parse_input(".5").unwrap_err();
+        parse_input("1.5.2").unwrap_err();
+        parse_input("99999999999999999999").unwrap_err();
     }
 
     #[test]
@@ -91,4 +129,30 @@ mod tests {
     fn test_invalid_boolean() {
         parse_input("\"true").unwrap_err();
     }
+
+    #[test]
+    fn test_null() {
+        assert_eq!(parse_input("null").unwrap(), ValueBasicData::Null);
+    }
+
+    #[test]
+    fn test_enum() {
+        assert_eq!(
+            parse_input("ADMIN").unwrap(),
+            ValueBasicData::Enum("ADMIN".to_string())
+        );
+        assert_eq!(
+            parse_input("nullable").unwrap(),
+            ValueBasicData::Enum("nullable".to_string())
+        );
+    }
+
+    #[test]
+    fn test_variable() {
+        assert_eq!(
+            parse_input("$first").unwrap(),
+            ValueBasicData::Variable("first".to_string())
+        );
+        parse_input("$ first").unwrap_err();
+    }
 }
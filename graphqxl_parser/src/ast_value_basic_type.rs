@@ -1,6 +1,7 @@
+use crate::ast_expandable_ref::parse_expandable_ref;
 use crate::parser::{Rule, RuleError};
 use crate::utils::unknown_rule_error;
-use crate::{Identifier, OwnedSpan};
+use crate::{ExpandableRef, Identifier, OwnedSpan};
 use pest::iterators::Pair;
 use std::fmt::{Display, Formatter};
 
@@ -10,20 +11,38 @@ pub enum ValueBasicType {
     Float,
     Boolean,
     String,
+    Id,
     Object(Identifier),
+    /// A generic instantiated in place, like `Connection<User>`, which the transpiler
+    /// replaces with an [ValueBasicType::Object] referencing an instance of it
+    Generic(Box<ExpandableRef>),
 }
 
 impl Display for ValueBasicType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", {
-            match self {
-                ValueBasicType::Int => "Int",
-                ValueBasicType::Float => "Float",
-                ValueBasicType::Boolean => "Boolean",
-                ValueBasicType::String => "String",
-                ValueBasicType::Object(id) => &id.id,
+        let name = match self {
+            ValueBasicType::Int => "Int",
+            ValueBasicType::Float => "Float",
+            ValueBasicType::Boolean => "Boolean",
+            ValueBasicType::String => "String",
+            ValueBasicType::Id => "ID",
+            ValueBasicType::Object(id) => &id.id,
+            ValueBasicType::Generic(expandable_ref) => {
+                write!(f, "{}<", expandable_ref.identifier.id)?;
+                let args = expandable_ref
+                    .generic_call
+                    .iter()
+                    .flat_map(|call| &call.args);
+                for (i, arg) in args.enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{arg}")?;
+                }
+                ">"
             }
-        })
+        };
+        write!(f, "{name}")
     }
 }
 
@@ -33,13 +52,18 @@ fn _parse_value_basic_type(pair: Pair<Rule>, file: &str) -> Result<ValueBasicTyp
         Rule::float => Ok(ValueBasicType::Float),
         Rule::string => Ok(ValueBasicType::String),
         Rule::boolean => Ok(ValueBasicType::Boolean),
+        Rule::id => Ok(ValueBasicType::Id),
         Rule::object => Ok(ValueBasicType::Object(Identifier {
             id: pair.as_str().to_string(),
             span: OwnedSpan::from(pair.as_span(), file),
         })),
+        Rule::generic_object => Ok(ValueBasicType::Generic(Box::new(parse_expandable_ref(
+            pair.into_inner().next().unwrap(),
+            file,
+        )?))),
         _unknown => Err(unknown_rule_error(
             pair,
-            "int, float, string, boolean or object",
+            "int, float, string, boolean, id, object or generic_object",
         )),
     }
 }
@@ -58,6 +82,7 @@ pub(crate) fn parse_value_basic_type(
 mod tests {
     use super::*;
     use crate::utils::parse_full_input;
+    use crate::ValueType;
 
     fn parse_input(input: &str) -> Result<ValueBasicType, Box<RuleError>> {
         parse_full_input(input, Rule::value_basic_type, parse_value_basic_type)
@@ -99,6 +124,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_generic_object() {
+        assert_eq!(
+            parse_input("Connection<User! [Int]>"),
+            Ok(ValueBasicType::Generic(Box::new(
+                ExpandableRef::from("Connection")
+                    .generic_arg(ValueType::object(Identifier::from("User")).non_nullable())
+                    .generic_arg(ValueType::int().array())
+            )))
+        );
+        assert_eq!(
+            parse_input("Connection<User! [Int]>").unwrap().to_string(),
+            "Connection<User!, [Int]>"
+        );
+    }
+
+    #[test]
+    fn test_id() {
+        assert_eq!(parse_input("ID").unwrap(), ValueBasicType::Id);
+        assert_eq!(
+            parse_input("IDentity"),
+            Ok(ValueBasicType::Object(Identifier::from("IDentity")))
+        );
+    }
+
+    #[test]
+    fn test_object_starting_with_basic_type_and_underscore() {
+        assert_eq!(
+            parse_input("Int_Range"),
+            Ok(ValueBasicType::Object(Identifier::from("Int_Range")))
+        );
+    }
+
     #[test]
     fn test_invalid_1() {
         parse_input("1DoNotStartWithNumber").unwrap_err();
@@ -22,7 +22,7 @@ impl ValueData {
     }
 
     pub fn float(float: f64) -> Self {
-        Self::build(ValueBasicData::Float(float))
+        Self::build(ValueBasicData::Float(format!("{float:?}")))
     }
 
     pub fn string(string: &str) -> Self {
@@ -33,6 +33,18 @@ impl ValueData {
         Self::build(ValueBasicData::Boolean(boolean))
     }
 
+    pub fn null() -> Self {
+        Self::build(ValueBasicData::Null)
+    }
+
+    pub fn enum_value(value: &str) -> Self {
+        Self::build(ValueBasicData::Enum(value.to_string()))
+    }
+
+    pub fn variable(name: &str) -> Self {
+        Self::build(ValueBasicData::Variable(name.to_string()))
+    }
+
     pub fn list(&mut self) -> Self {
         ValueData::List(vec![self.clone()])
     }
@@ -133,6 +145,20 @@ mod tests {
         parse_input("{ a: { b: { c { d: true }} } }").unwrap_err();
     }
 
+    #[test]
+    fn test_enums_and_nulls_inside_lists_and_objects() {
+        assert_eq!(
+            parse_input("[ADMIN, null, { role: USER, limit: -1e3 }]"),
+            Ok(ValueData::enum_value("ADMIN")
+                .list()
+                .push(ValueData::null())
+                .push(ValueData::enum_value("USER").to_object("role").insert(
+                    "limit",
+                    ValueData::build(ValueBasicData::Float("-1e3".to_string()))
+                )))
+        );
+    }
+
     #[test]
     fn test_nested_list() {
         let mut one_two = ValueData::int(1).list().push(ValueData::int(2));
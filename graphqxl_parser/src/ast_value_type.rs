@@ -1,8 +1,9 @@
 use crate::ast_value_basic_type::{parse_value_basic_type, ValueBasicType};
 use crate::parser::{Rule, RuleError};
 use crate::utils::unknown_rule_error;
-use crate::{Identifier, OwnedSpan};
+use crate::{Diagnostic, DiagnosticCode, Identifier, OwnedSpan};
 use pest::iterators::Pair;
+use std::fmt::{Display, Formatter};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ValueType {
@@ -40,6 +41,10 @@ impl ValueType {
         Self::build(ValueBasicType::Boolean)
     }
 
+    pub fn id() -> Self {
+        Self::build(ValueBasicType::Id)
+    }
+
     pub fn object(identifier: Identifier) -> Self {
         Self::build(ValueBasicType::Object(identifier))
     }
@@ -60,10 +65,19 @@ impl ValueType {
         }
     }
 
-    pub fn replace_basic_type(&mut self, value: ValueType) -> Result<(), Box<RuleError>> {
+    pub fn retrieve_basic_type_mut(&mut self) -> &mut ValueBasicType {
+        match self {
+            ValueType::Basic(b, _) => b,
+            ValueType::Array(a, _) => ValueType::retrieve_basic_type_mut(a),
+            ValueType::NonNullable(a, _) => ValueType::retrieve_basic_type_mut(a),
+        }
+    }
+
+    pub fn replace_basic_type(&mut self, value: ValueType) -> Result<(), Box<Diagnostic>> {
         if let ValueType::NonNullable(_, _) = value {
             if let ValueType::NonNullable(_, _) = self {
                 return Err(value.span().make_error(
+                    DiagnosticCode::InvalidType,
                     "cannot use a non-nullable type inside another non-nullable type",
                 ));
             }
@@ -77,6 +91,16 @@ impl ValueType {
     }
 }
 
+impl Display for ValueType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValueType::Basic(basic, _) => write!(f, "{basic}"),
+            ValueType::Array(inner, _) => write!(f, "[{inner}]"),
+            ValueType::NonNullable(inner, _) => write!(f, "{inner}!"),
+        }
+    }
+}
+
 pub(crate) fn parse_value_type(pair: Pair<Rule>, file: &str) -> Result<ValueType, Box<RuleError>> {
     let span = OwnedSpan::from(pair.as_span(), file);
     match pair.as_rule() {
@@ -188,4 +212,12 @@ mod tests {
     fn test_not_parses_double_nullable() {
         parse_input("[Int!!]").unwrap_err();
     }
+
+    #[test]
+    fn test_displays_nested_value_type() {
+        assert_eq!(
+            parse_input("[[Int!]]!").unwrap().to_string(),
+            "[[Int!]]!".to_string()
+        );
+    }
 }
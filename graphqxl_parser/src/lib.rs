@@ -15,6 +15,7 @@ mod ast_function_call;
 mod ast_generic;
 mod ast_generic_block_def;
 mod ast_generic_call;
+mod ast_generic_union;
 mod ast_identifier;
 mod ast_implements;
 mod ast_import;
@@ -43,8 +44,10 @@ pub use ast_function_call::*;
 pub use ast_generic::*;
 pub use ast_generic_block_def::*;
 pub use ast_generic_call::*;
+pub use ast_generic_union::*;
 pub use ast_identifier::*;
 pub use ast_implements::*;
+pub use ast_import::*;
 pub use ast_scalar::*;
 pub use ast_schema::*;
 pub use ast_spec::*;
@@ -53,4 +56,8 @@ pub use ast_value_basic_data::*;
 pub use ast_value_basic_type::*;
 pub use ast_value_data::*;
 pub use ast_value_type::*;
-pub use utils::OwnedSpan;
+pub use parser::{Rule, RuleError};
+pub use utils::{
+    Diagnostic, DiagnosticCode, DiagnosticSpan, FsSourceResolver, InMemorySourceResolver,
+    MultipleErrors, OwnedSpan, Severity, SourceResolver,
+};
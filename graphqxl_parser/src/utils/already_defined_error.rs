@@ -1,11 +1,20 @@
-use crate::parser::{Rule, RuleError};
+use crate::parser::Rule;
+use crate::{Diagnostic, DiagnosticCode, DiagnosticSpan, OwnedSpan};
 use pest::iterators::Pair;
 
-pub(crate) fn already_defined_error(pair: Pair<Rule>, kind: &str, name: &str) -> Box<RuleError> {
-    Box::new(pest::error::Error::new_from_span(
-        pest::error::ErrorVariant::CustomError {
-            message: kind.to_string() + " \"" + name + "\" is already defined",
-        },
-        pair.as_span(),
-    ))
+pub(crate) fn already_defined_error(
+    pair: Pair<Rule>,
+    file: &str,
+    kind: &str,
+    name: &str,
+    previous: Option<&OwnedSpan>,
+) -> Box<Diagnostic> {
+    let mut err = OwnedSpan::from(pair.as_span(), file).make_error(
+        DiagnosticCode::DuplicatedDefinition,
+        &(kind.to_string() + " \"" + name + "\" is already defined"),
+    );
+    if let Some(previous) = previous {
+        err.secondary(DiagnosticSpan::from(previous).label("first defined here"));
+    }
+    err
 }
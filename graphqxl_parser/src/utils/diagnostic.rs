@@ -0,0 +1,378 @@
+use crate::parser::RuleError;
+use crate::OwnedSpan;
+use pest::error::{ErrorVariant, InputLocation, LineColLocation};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Stable identifier of each kind of problem, meant for tools that consume the
+/// diagnostics, so it must not change even if the message does
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DiagnosticCode {
+    SyntaxError,
+    DuplicatedDefinition,
+    ImportNotFound,
+    CyclicalImport,
+    ImportConflict,
+    UndefinedImport,
+    NotImported,
+    UndefinedReference,
+    UndefinedField,
+    RepeatedField,
+    InvalidOverride,
+    SpreadDepthExceeded,
+    CyclicalSpread,
+    InvalidGeneric,
+    InvalidType,
+    InvalidDescription,
+    GraphqlValidation,
+    Other,
+}
+
+impl DiagnosticCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticCode::SyntaxError => "syntax-error",
+            DiagnosticCode::DuplicatedDefinition => "duplicated-definition",
+            DiagnosticCode::ImportNotFound => "import-not-found",
+            DiagnosticCode::CyclicalImport => "cyclical-import",
+            DiagnosticCode::ImportConflict => "import-conflict",
+            DiagnosticCode::UndefinedImport => "undefined-import",
+            DiagnosticCode::NotImported => "not-imported",
+            DiagnosticCode::UndefinedReference => "undefined-reference",
+            DiagnosticCode::UndefinedField => "undefined-field",
+            DiagnosticCode::RepeatedField => "repeated-field",
+            DiagnosticCode::InvalidOverride => "invalid-override",
+            DiagnosticCode::SpreadDepthExceeded => "spread-depth-exceeded",
+            DiagnosticCode::CyclicalSpread => "cyclical-spread",
+            DiagnosticCode::InvalidGeneric => "invalid-generic",
+            DiagnosticCode::InvalidType => "invalid-type",
+            DiagnosticCode::InvalidDescription => "invalid-description",
+            DiagnosticCode::GraphqlValidation => "graphql-validation",
+            DiagnosticCode::Other => "other",
+        }
+    }
+}
+
+impl Display for DiagnosticCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// Place in a .graphqxl file, lines and columns are one based, the end column is
+/// exclusive, and `start` and `end` are byte offsets
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DiagnosticSpan {
+    pub file: String,
+    pub line: usize,
+    pub col: usize,
+    pub end_line: usize,
+    pub end_col: usize,
+    pub start: usize,
+    pub end: usize,
+    pub label: Option<String>,
+    /// Text of the line where the span starts, for rendering it
+    source_line: String,
+    /// Text of the line where the span ends, at least up to its end
+    end_source_line: String,
+}
+
+impl DiagnosticSpan {
+    pub fn from(span: &OwnedSpan) -> Self {
+        let (end_line, end_col) = match span.input.rsplit_once('\n') {
+            Some((before, last_line)) => (
+                span.line + before.matches('\n').count() + 1,
+                last_line.chars().count() + 1,
+            ),
+            None => (span.line, span.col + span.input.chars().count()),
+        };
+        Self {
+            file: span.file.clone(),
+            line: span.line,
+            col: span.col,
+            end_line,
+            end_col,
+            start: span.start,
+            end: span.end,
+            label: None,
+            source_line: span.err_placeholder.line().to_string(),
+            end_source_line: match span.input.rsplit_once('\n') {
+                Some((_, last_line)) => last_line.to_string(),
+                None => span.err_placeholder.line().to_string(),
+            },
+        }
+    }
+
+    /// Text of the line where the span starts, needed to translate its columns,
+    /// that count characters, into other units
+    pub fn source_line(&self) -> &str {
+        &self.source_line
+    }
+
+    /// Text of the line where the span ends, at least up to its end
+    pub fn end_source_line(&self) -> &str {
+        &self.end_source_line
+    }
+
+    pub fn label(&mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self.clone()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub code: DiagnosticCode,
+    pub severity: Severity,
+    pub message: String,
+    pub primary: Option<DiagnosticSpan>,
+    pub secondary: Vec<DiagnosticSpan>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn new(code: DiagnosticCode, message: &str) -> Self {
+        Self {
+            code,
+            severity: Severity::Error,
+            message: message.to_string(),
+            primary: None,
+            secondary: vec![],
+            notes: vec![],
+        }
+    }
+
+    pub(crate) fn from_span(span: &OwnedSpan, code: DiagnosticCode, message: &str) -> Self {
+        Self {
+            primary: Some(DiagnosticSpan::from(span)),
+            ..Self::new(code, message)
+        }
+    }
+
+    /// Builds a diagnostic out of any error, errors that are not already a
+    /// diagnostic are considered syntax errors if they come from the parser
+    pub fn from_error(err: &(dyn Error + 'static)) -> Self {
+        if let Some(diagnostic) = err.downcast_ref::<Diagnostic>() {
+            return diagnostic.clone();
+        }
+        if let Some(diagnostic) = err.downcast_ref::<Box<Diagnostic>>() {
+            return diagnostic.as_ref().clone();
+        }
+        let rule_error = err
+            .downcast_ref::<RuleError>()
+            .or_else(|| err.downcast_ref::<Box<RuleError>>().map(|err| err.as_ref()));
+        let Some(rule_error) = rule_error else {
+            return Self::new(DiagnosticCode::Other, &err.to_string());
+        };
+        let ((line, col), (end_line, end_col)) = match rule_error.line_col {
+            LineColLocation::Pos(pos) => (pos, pos),
+            LineColLocation::Span(start, end) => (start, end),
+        };
+        let (start, end) = match rule_error.location {
+            InputLocation::Pos(pos) => (pos, pos),
+            InputLocation::Span(span) => span,
+        };
+        let message = match &rule_error.variant {
+            ErrorVariant::CustomError { message } => message.clone(),
+            variant => variant.message().to_string(),
+        };
+        Self {
+            primary: Some(DiagnosticSpan {
+                file: rule_error.path().unwrap_or_default().to_string(),
+                line,
+                col,
+                end_line,
+                end_col,
+                start,
+                end,
+                label: None,
+                source_line: rule_error.line().to_string(),
+                // pest does not expose the line where a span ends, but its
+                // syntax errors are reported at a single position anyway
+                end_source_line: rule_error.line().to_string(),
+            }),
+            ..Self::new(DiagnosticCode::SyntaxError, &message)
+        }
+    }
+
+    /// Sets the label of the primary span
+    pub fn label(&mut self, label: &str) -> Self {
+        if let Some(primary) = &mut self.primary {
+            primary.label = Some(label.to_string());
+        }
+        self.clone()
+    }
+
+    pub fn severity(&mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self.clone()
+    }
+
+    pub fn secondary(&mut self, span: DiagnosticSpan) -> Self {
+        self.secondary.push(span);
+        self.clone()
+    }
+
+    pub fn note(&mut self, note: &str) -> Self {
+        self.notes.push(note.to_string());
+        self.clone()
+    }
+}
+
+const BOLD: &str = "1";
+const RED: &str = "1;31";
+const YELLOW: &str = "1;33";
+const BLUE: &str = "1;34";
+
+fn paint(color: bool, style: &str, text: &str) -> String {
+    if color {
+        format!("\x1b[{style}m{text}\x1b[0m")
+    } else {
+        text.to_string()
+    }
+}
+
+impl Diagnostic {
+    /// Renders the diagnostic with a code frame for each one of its spans, the
+    /// primary one underlined with `^` and the secondary ones with `-`
+    pub fn render(&self, color: bool) -> String {
+        let marker_style = match self.severity {
+            Severity::Error => RED,
+            Severity::Warning => YELLOW,
+        };
+        let width = self
+            .primary
+            .iter()
+            .chain(self.secondary.iter())
+            .map(|span| span.line.to_string().len())
+            .max()
+            .unwrap_or(0);
+        let gutter = " ".repeat(width);
+        let bar = paint(color, BLUE, "|");
+
+        let mut out = format!(
+            "{}{}",
+            paint(
+                color,
+                marker_style,
+                &format!("{}[{}]", self.severity, self.code)
+            ),
+            paint(color, BOLD, &format!(": {}", self.message))
+        );
+        let frames = self
+            .primary
+            .iter()
+            .map(|span| (span, "-->", '^', marker_style))
+            .chain(self.secondary.iter().map(|span| (span, ":::", '-', BLUE)));
+        for (span, arrow, marker, style) in frames {
+            let location = if span.file.is_empty() {
+                format!("{}:{}", span.line, span.col)
+            } else {
+                format!("{}:{}:{}", span.file, span.line, span.col)
+            };
+            let source_line = span.source_line.trim_end_matches(['\n', '\r', '␊']);
+            let indent: String = source_line
+                .chars()
+                .take(span.col.saturating_sub(1))
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let length = if span.end_line == span.line {
+                span.end_col.saturating_sub(span.col)
+            } else {
+                (source_line.chars().count() + 1).saturating_sub(span.col)
+            };
+            let mut markers = marker.to_string().repeat(length.max(1));
+            if let Some(label) = &span.label {
+                markers = format!("{markers} {label}");
+            }
+            out += &format!(
+                "\n{gutter}{} {location}\n{gutter} {bar}\n{} {bar} {source_line}\n{gutter} {bar} {indent}{}",
+                paint(color, BLUE, arrow),
+                paint(color, BLUE, &format!("{:>width$}", span.line)),
+                paint(color, style, &markers)
+            );
+        }
+        for note in self.notes.iter() {
+            out += &format!("\n{gutter} {} note: {note}", paint(color, BLUE, "="));
+        }
+        out
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(false))
+    }
+}
+
+impl Error for Diagnostic {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_spec_with_resolver, InMemorySourceResolver};
+
+    fn diagnostic(source: &str) -> Diagnostic {
+        let resolver = InMemorySourceResolver::new().source("main.graphqxl", source);
+        let err = parse_spec_with_resolver("main.graphqxl", &resolver).unwrap_err();
+        Diagnostic::from_error(err.as_ref())
+    }
+
+    #[test]
+    fn test_syntax_errors_are_diagnostics() {
+        let diagnostic = diagnostic("type A {\n  a Int\n}");
+        assert_eq!(diagnostic.code, DiagnosticCode::SyntaxError);
+        assert_eq!(diagnostic.severity, Severity::Error);
+        let primary = diagnostic.primary.unwrap();
+        assert!(primary.file.ends_with("main.graphqxl"));
+        assert_eq!((primary.line, primary.col), (2, 5));
+    }
+
+    #[test]
+    fn test_span_errors_keep_the_span() {
+        let diagnostic = diagnostic("type A {\n  a: Int\n}\ntype A {\n  a: Int\n}");
+        assert_eq!(diagnostic.code, DiagnosticCode::DuplicatedDefinition);
+        assert_eq!(diagnostic.message, "type \"A\" is already defined");
+        let primary = diagnostic.primary.unwrap();
+        assert_eq!((primary.line, primary.col), (4, 1));
+        assert_eq!((primary.end_line, primary.end_col), (6, 2));
+        assert_eq!((primary.start, primary.end), (20, 39));
+    }
+
+    #[test]
+    fn test_renders_code_frames() {
+        let mut diagnostic = diagnostic("scalar A\nscalar A");
+        diagnostic.primary.as_mut().unwrap().file = "main.graphqxl".to_string();
+        diagnostic.secondary[0].file = "main.graphqxl".to_string();
+        assert_eq!(
+            diagnostic.render(false),
+            r#"error[duplicated-definition]: scalar "A" is already defined
+ --> main.graphqxl:2:1
+  |
+2 | scalar A
+  | ^^^^^^^^
+ ::: main.graphqxl:1:8
+  |
+1 | scalar A
+  |        - first defined here"#
+        );
+        assert!(diagnostic
+            .render(true)
+            .contains("\x1b[1;31m^^^^^^^^\x1b[0m"));
+    }
+}
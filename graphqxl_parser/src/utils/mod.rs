@@ -1,12 +1,22 @@
 mod already_defined_error;
 mod custom_error;
+mod diagnostic;
+mod multiple_errors;
 mod owned_span;
 mod parse_full_input;
+mod source_resolver;
+mod spec_references;
+mod string_value;
 pub(crate) mod unknown_rule_error;
 
 pub(crate) use already_defined_error::*;
 pub(crate) use custom_error::*;
+pub use diagnostic::*;
+pub use multiple_errors::*;
 pub use owned_span::*;
+pub use source_resolver::*;
+pub(crate) use spec_references::*;
+pub(crate) use string_value::*;
 pub(crate) use unknown_rule_error::*;
 
 #[allow(unused_imports)]
@@ -0,0 +1,112 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Independent errors found in the same run, so that all of them can be
+/// reported at once instead of stopping at the first one
+#[derive(Debug, Default)]
+pub struct MultipleErrors(Vec<Box<dyn Error>>);
+
+impl MultipleErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an error, flattening it if it already contains multiple errors. The
+    /// same error can be reached from several places, for example a type with
+    /// problems that is spread into others, so it is only kept once
+    pub fn push(&mut self, err: Box<dyn Error>) {
+        match err.downcast::<MultipleErrors>() {
+            Ok(multiple) => {
+                for err in multiple.0 {
+                    self.push(err)
+                }
+            }
+            Err(err) => {
+                let message = err.to_string();
+                if !self.0.iter().any(|other| other.to_string() == message) {
+                    self.0.push(err)
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn errors(&self) -> &[Box<dyn Error>] {
+        &self.0
+    }
+
+    /// Splits an error into the individual errors it is made of
+    pub fn flatten(err: Box<dyn Error>) -> Vec<Box<dyn Error>> {
+        let mut errors = Self::new();
+        errors.push(err);
+        errors.0
+    }
+
+    /// Returns `ok` if no error was collected, the error itself if there was
+    /// only one, or all of them together
+    pub fn into_result<T>(mut self, ok: T) -> Result<T, Box<dyn Error>> {
+        match self.0.len() {
+            0 => Ok(ok),
+            1 => Err(self.0.remove(0)),
+            _ => Err(Box::new(self)),
+        }
+    }
+}
+
+impl Display for MultipleErrors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for err in self.0.iter() {
+            write!(f, "{err}\n\n")?;
+        }
+        write!(f, "Found {} errors", self.0.len())
+    }
+}
+
+impl Error for MultipleErrors {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DiagnosticCode, OwnedSpan};
+
+    fn err(msg: &str) -> Box<dyn Error> {
+        OwnedSpan::default().make_error(DiagnosticCode::Other, msg)
+    }
+
+    #[test]
+    fn test_single_error_is_returned_as_is() {
+        let mut errors = MultipleErrors::new();
+        errors.push(err("first"));
+        let result = errors.into_result(()).unwrap_err();
+        assert!(result.downcast_ref::<MultipleErrors>().is_none());
+        assert!(result.to_string().contains("first"));
+    }
+
+    #[test]
+    fn test_nested_errors_are_flattened() {
+        let mut inner = MultipleErrors::new();
+        inner.push(err("first"));
+        inner.push(err("second"));
+        let mut errors = MultipleErrors::new();
+        errors.push(inner.into_result(()).unwrap_err());
+        errors.push(err("third"));
+        let result = errors.into_result(()).unwrap_err();
+        assert_eq!(MultipleErrors::flatten(result).len(), 3);
+    }
+
+    #[test]
+    fn test_repeated_errors_are_kept_once() {
+        let mut errors = MultipleErrors::new();
+        errors.push(err("first"));
+        errors.push(err("first"));
+        assert_eq!(errors.errors().len(), 1);
+    }
+
+    #[test]
+    fn test_no_errors_is_ok() {
+        assert_eq!(MultipleErrors::new().into_result(1).unwrap(), 1);
+    }
+}
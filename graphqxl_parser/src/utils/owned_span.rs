@@ -1,6 +1,7 @@
 use pest::Span;
 
 use crate::parser::RuleError;
+use crate::{Diagnostic, DiagnosticCode};
 
 #[derive(Clone, Debug)]
 pub struct OwnedSpan {
@@ -34,12 +35,8 @@ impl Default for OwnedSpan {
 }
 
 impl OwnedSpan {
-    pub fn make_error(&self, msg: &str) -> Box<RuleError> {
-        let mut err = self.err_placeholder.clone();
-        err.variant = pest::error::ErrorVariant::CustomError {
-            message: format!("{}:{} {}", self.file, self.line, msg),
-        };
-        Box::new(err)
+    pub fn make_error(&self, code: DiagnosticCode, msg: &str) -> Box<Diagnostic> {
+        Box::new(Diagnostic::from_span(self, code, msg))
     }
 }
 
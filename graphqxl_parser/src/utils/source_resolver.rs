@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+pub trait SourceResolver {
+    /// Returns the unique path that identifies the source, failing if it does not exist
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf, Box<dyn Error>>;
+
+    /// Checks if the source exists without reading it
+    fn exists(&self, path: &Path) -> bool;
+
+    /// Reads the content of an already canonicalized source
+    fn read_to_string(&self, path: &Path) -> Result<String, Box<dyn Error>>;
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FsSourceResolver;
+
+impl SourceResolver for FsSourceResolver {
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        Ok(fs::canonicalize(path)?)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String, Box<dyn Error>> {
+        Ok(fs::read_to_string(path)?)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct InMemorySourceResolver {
+    sources: HashMap<PathBuf, String>,
+}
+
+impl InMemorySourceResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn source(&mut self, path: &str, content: &str) -> Self {
+        self.sources
+            .insert(normalize_path(Path::new(path)), content.to_string());
+        self.clone()
+    }
+}
+
+/// Resolves "." and ".." components without touching the filesystem, anchoring
+/// the result to the root so that relative and absolute paths are comparable
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::from("/");
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+        }
+    }
+    normalized
+}
+
+impl SourceResolver for InMemorySourceResolver {
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        let normalized = normalize_path(path);
+        if self.sources.contains_key(&normalized) {
+            Ok(normalized)
+        } else {
+            Err(Box::new(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("source {:?} does not exist", path),
+            )))
+        }
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.sources.contains_key(&normalize_path(path))
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String, Box<dyn Error>> {
+        let path = self.canonicalize(path)?;
+        Ok(self.sources.get(&path).unwrap().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_memory_normalizes_paths() {
+        let resolver = InMemorySourceResolver::new().source("dir/file.graphqxl", "type A {}");
+        assert_eq!(
+            resolver
+                .canonicalize(Path::new("./dir/other/../file.graphqxl"))
+                .unwrap(),
+            PathBuf::from("/dir/file.graphqxl")
+        );
+        assert!(resolver.exists(Path::new("/dir/file.graphqxl")));
+    }
+
+    #[test]
+    fn test_in_memory_reads_source() {
+        let resolver = InMemorySourceResolver::new().source("file.graphqxl", "type A {}");
+        assert_eq!(
+            resolver.read_to_string(Path::new("file.graphqxl")).unwrap(),
+            "type A {}"
+        );
+    }
+
+    #[test]
+    fn test_in_memory_fails_on_missing_source() {
+        let resolver = InMemorySourceResolver::new();
+        resolver
+            .canonicalize(Path::new("file.graphqxl"))
+            .unwrap_err();
+        assert!(!resolver.exists(Path::new("file.graphqxl")));
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{
+    Argument, BlockDef, BlockEntry, ExpandableRef, Identifier, ModifiedRef, Spec, UnionMember,
+    ValueBasicType, ValueType,
+};
+
+fn value_type_references(
+    value_type: &mut ValueType,
+    generic_args: &[String],
+    f: &mut dyn FnMut(&mut Identifier),
+) {
+    match value_type {
+        ValueType::Basic(ValueBasicType::Object(identifier), _) => {
+            if !generic_args.contains(&identifier.id) {
+                f(identifier)
+            }
+        }
+        ValueType::Basic(ValueBasicType::Generic(expandable_ref), _) => {
+            expandable_ref_references(expandable_ref, generic_args, f)
+        }
+        ValueType::Basic(..) => {}
+        ValueType::Array(inner, _) => value_type_references(inner, generic_args, f),
+        ValueType::NonNullable(inner, _) => value_type_references(inner, generic_args, f),
+    }
+}
+
+fn args_references(
+    args: &mut [Argument],
+    generic_args: &[String],
+    f: &mut dyn FnMut(&mut Identifier),
+) {
+    for arg in args.iter_mut() {
+        value_type_references(&mut arg.value_type, generic_args, f);
+    }
+}
+
+fn expandable_ref_references(
+    expandable_ref: &mut ExpandableRef,
+    generic_args: &[String],
+    f: &mut dyn FnMut(&mut Identifier),
+) {
+    if !generic_args.contains(&expandable_ref.identifier.id) {
+        f(&mut expandable_ref.identifier)
+    }
+    if let Some(generic_call) = &mut expandable_ref.generic_call {
+        for arg in generic_call.args.iter_mut() {
+            value_type_references(arg, generic_args, f);
+        }
+    }
+}
+
+fn modified_ref_references(
+    modified_ref: &mut ModifiedRef,
+    generic_args: &[String],
+    f: &mut dyn FnMut(&mut Identifier),
+) {
+    match modified_ref {
+        ModifiedRef::Required(inner, _) => modified_ref_references(inner, generic_args, f),
+        ModifiedRef::Optional(inner, _) => modified_ref_references(inner, generic_args, f),
+        ModifiedRef::Omit(inner, _, _) => modified_ref_references(inner, generic_args, f),
+        ModifiedRef::Pick(inner, _, _) => modified_ref_references(inner, generic_args, f),
+        ModifiedRef::ExpandableRef(expandable_ref) => {
+            expandable_ref_references(expandable_ref, generic_args, f)
+        }
+    }
+}
+
+fn block_def_references(block_def: &mut BlockDef, f: &mut dyn FnMut(&mut Identifier)) {
+    if block_def.extend {
+        f(&mut block_def.name);
+    }
+    let generic_args: Vec<String> = match &block_def.generic {
+        Some(generic) => generic.args.iter().map(|arg| arg.id.clone()).collect(),
+        None => Vec::new(),
+    };
+    if let Some(generic) = &mut block_def.generic {
+        for default in generic.defaults.iter_mut().flatten() {
+            value_type_references(default, &generic_args, f);
+        }
+    }
+    if let Some(implements) = &mut block_def.implements {
+        for interface in implements.interfaces.iter_mut() {
+            f(interface);
+        }
+    }
+    for entry in block_def.entries.iter_mut() {
+        match entry {
+            BlockEntry::Field(field) => {
+                if let Some(value_type) = &mut field.value_type {
+                    value_type_references(value_type, &generic_args, f);
+                }
+                args_references(&mut field.args, &generic_args, f);
+            }
+            BlockEntry::SpreadRef(modified_ref) => {
+                modified_ref_references(modified_ref, &generic_args, f)
+            }
+        }
+    }
+}
+
+/// Calls `f` with every identifier in the spec that points to a type, input, enum,
+/// interface, scalar or union defined somewhere else, including the names of
+/// extensions. Generic parameters and directives are not considered references.
+pub(crate) fn for_each_reference(spec: &mut Spec, f: &mut dyn FnMut(&mut Identifier)) {
+    for block_def in spec
+        .types
+        .values_mut()
+        .chain(spec.inputs.values_mut())
+        .chain(spec.enums.values_mut())
+        .chain(spec.interfaces.values_mut())
+    {
+        block_def_references(block_def, f);
+    }
+    for generic_block_def in spec
+        .generic_types
+        .values_mut()
+        .chain(spec.generic_inputs.values_mut())
+        .chain(spec.generic_interfaces.values_mut())
+    {
+        modified_ref_references(&mut generic_block_def.modified_ref, &[], f);
+    }
+    for scalar in spec.scalars.values_mut() {
+        if scalar.extend {
+            f(&mut scalar.name);
+        }
+    }
+    for union in spec.unions.values_mut() {
+        if union.extend {
+            f(&mut union.name);
+        }
+        let generic_args: Vec<String> = match &union.generic {
+            Some(generic) => generic.args.iter().map(|arg| arg.id.clone()).collect(),
+            None => Vec::new(),
+        };
+        for member in union.members.iter_mut() {
+            let (UnionMember::Type(member) | UnionMember::Spread(member)) = member;
+            if !generic_args.contains(&member.id) {
+                f(member);
+            }
+        }
+    }
+    for generic_union in spec.generic_unions.values_mut() {
+        expandable_ref_references(&mut generic_union.expandable_ref, &[], f);
+    }
+    for directive_def in spec.directives.values_mut() {
+        args_references(&mut directive_def.arguments, &[], f);
+    }
+    for schema in spec.schemas.values_mut() {
+        for root in [
+            &mut schema.query,
+            &mut schema.mutation,
+            &mut schema.subscription,
+        ] {
+            if !root.id.is_empty() {
+                f(root);
+            }
+        }
+    }
+}
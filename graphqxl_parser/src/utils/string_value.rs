@@ -0,0 +1,139 @@
+/// Resolves the escape sequences of the content of a `"quoted"` string. The grammar
+/// already guarantees that every escape sequence is well formed.
+pub(crate) fn string_value(raw: &str) -> Result<String, String> {
+    let mut result = String::new();
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('b') => result.push('\u{8}'),
+            Some('f') => result.push('\u{c}'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('t') => result.push('\t'),
+            Some('u') => {
+                let code = hex_code(&mut chars);
+                let code = if (0xD800..0xDC00).contains(&code) {
+                    // a leading surrogate needs to be followed by a trailing one
+                    let mut rest = chars.clone();
+                    if rest.next() != Some('\\') || rest.next() != Some('u') {
+                        return Err(format!("invalid unicode escape \\u{code:X}"));
+                    }
+                    let low = hex_code(&mut rest);
+                    if !(0xDC00..0xE000).contains(&low) {
+                        return Err(format!("invalid unicode escape \\u{code:X}\\u{low:X}"));
+                    }
+                    chars = rest;
+                    0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00)
+                } else {
+                    code
+                };
+                match char::from_u32(code) {
+                    Some(c) => result.push(c),
+                    None => return Err(format!("invalid unicode escape \\u{code:X}")),
+                }
+            }
+            // \" \\ and \/ stand for themselves
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+    Ok(result)
+}
+
+fn hex_code(chars: &mut std::str::Chars) -> u32 {
+    let hex: String = chars.take(4).collect();
+    u32::from_str_radix(&hex, 16).unwrap_or_default()
+}
+
+fn leading_whitespace(line: &str) -> usize {
+    line.len() - line.trim_start_matches([' ', '\t']).len()
+}
+
+fn is_blank(line: &str) -> bool {
+    leading_whitespace(line) == line.len()
+}
+
+/// Computes the value of the content of a `"""block string"""` as defined by the
+/// GraphQL spec: `\"""` stands for `"""`, the indentation that all the lines but the
+/// first one have in common is removed, and so are the leading and trailing blank lines
+pub(crate) fn block_string_value(raw: &str) -> String {
+    let raw = raw.replace("\\\"\"\"", "\"\"\"");
+    let lines: Vec<&str> = raw
+        .split("\r\n")
+        .flat_map(|l| l.split(['\n', '\r']))
+        .collect();
+
+    let common_indent = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !is_blank(line))
+        .map(|line| leading_whitespace(line))
+        .min()
+        .unwrap_or(0);
+
+    let mut lines: Vec<&str> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 {
+                line
+            } else {
+                &line[common_indent.min(leading_whitespace(line))..]
+            }
+        })
+        .collect();
+
+    while lines.first().map(|line| is_blank(line)).unwrap_or(false) {
+        lines.remove(0);
+    }
+    while lines.last().map(|line| is_blank(line)).unwrap_or(false) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolves_escapes() {
+        assert_eq!(
+            string_value(r#"a \"b\" \\ \/ \n\t"#),
+            Ok("a \"b\" \\ / \n\t".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolves_unicode_escapes() {
+        assert_eq!(string_value(r"caf\u00E9"), Ok("café".to_string()));
+        assert_eq!(string_value(r"\uD83D\uDE00"), Ok("😀".to_string()));
+        string_value(r"\uD83D").unwrap_err();
+        string_value(r"\uDE00").unwrap_err();
+    }
+
+    #[test]
+    fn test_removes_common_indentation() {
+        assert_eq!(
+            block_string_value("\n    Example:\n\n      query {\n        a\n      }\n    "),
+            "Example:\n\n  query {\n    a\n  }"
+        );
+    }
+
+    #[test]
+    fn test_keeps_first_line_and_trailing_whitespace() {
+        assert_eq!(
+            block_string_value("first  \n  second  "),
+            "first  \nsecond  "
+        );
+    }
+
+    #[test]
+    fn test_unescapes_triple_quotes() {
+        assert_eq!(block_string_value(r#" a \""" b \n c"#), " a \"\"\" b \\n c");
+    }
+}
@@ -1,8 +1,9 @@
+use crate::synth_block_def::BlockDefSynth;
 use crate::synth_spec::SpecSynth;
 pub use crate::synths::SourceMapEntry;
 pub use crate::synths::SynthConfig;
 use crate::synths::{Synth, SynthContext};
-use graphqxl_parser::Spec;
+use graphqxl_parser::{BlockDef, Spec};
 
 mod synth_arguments;
 mod synth_block_def;
@@ -34,3 +35,12 @@ pub fn synth_spec(spec: Spec, config: SynthConfig) -> (String, Vec<SourceMapEntr
     SpecSynth(spec).synth(&mut context);
     (context.result, context.source_map)
 }
+
+pub fn synth_block_def(block_def: BlockDef, config: SynthConfig) -> String {
+    let mut context = SynthContext {
+        config,
+        ..Default::default()
+    };
+    BlockDefSynth(block_def).synth(&mut context);
+    context.result
+}
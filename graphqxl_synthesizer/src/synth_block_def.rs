@@ -9,10 +9,6 @@ pub(crate) struct BlockDefSynth(pub(crate) BlockDef);
 
 impl Synth for BlockDefSynth {
     fn synth(&self, context: &mut SynthContext) -> bool {
-        if self.0.name.id.starts_with(&context.config.private_prefix) {
-            return false;
-        }
-
         let mut v: Vec<Box<dyn Synth>> = match self.0.extend {
             true => vec![Box::new(StringSynth::from("extend "))],
             false => vec![],
@@ -28,7 +24,7 @@ impl Synth for BlockDefSynth {
         v.push(Box::new(StringSynth::from(" ")));
         v.push(Box::new(IdentifierSynth(self.0.name.clone())));
         if let Some(implements) = &self.0.implements {
-            let first = implements.interfaces.get(0).unwrap();
+            let first = implements.interfaces.first().unwrap();
             v.push(Box::new(StringSynth::from(" implements ")));
             v.push(Box::new(IdentifierSynth(first.clone())));
             for i in 1..implements.interfaces.len() {
@@ -44,7 +40,7 @@ impl Synth for BlockDefSynth {
         let mut inner_synths = Vec::new();
         for entry in self.0.entries.iter() {
             if let BlockEntry::Field(block_field) = entry {
-                inner_synths.push(BlockFieldSynth(block_field.clone()));
+                inner_synths.push(BlockFieldSynth(*block_field.clone()));
             }
         }
         if !(inner_synths.is_empty() && self.0.extend) {
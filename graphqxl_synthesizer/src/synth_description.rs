@@ -1,16 +1,50 @@
 use crate::synths::{Synth, SynthContext};
-use crate::utils::escape_non_escaped_quotes;
 
 pub(crate) struct DescriptionSynth {
     pub(crate) text: String,
     pub(crate) is_multiline: bool,
 }
 
+/// Whether parsing `text` as the content of a block string, with every line indented,
+/// gives `text` back. Block strings lose leading and trailing blank lines, the
+/// indentation that all the lines have in common and the difference between line breaks,
+/// and cannot escape control characters.
+fn fits_in_block_string(text: &str) -> bool {
+    let is_blank = |line: &str| line.trim_matches([' ', '\t']).is_empty();
+    let lines: Vec<&str> = text.split('\n').collect();
+    !text
+        .chars()
+        .any(|c| c.is_control() && c != '\n' && c != '\t')
+        && !lines.first().map(|l| is_blank(l)).unwrap_or(true)
+        && !lines.last().map(|l| is_blank(l)).unwrap_or(true)
+        && lines
+            .iter()
+            .any(|line| !is_blank(line) && !line.starts_with([' ', '\t']))
+}
+
+fn escape_string(text: &str) -> String {
+    let mut result = String::new();
+    for c in text.chars() {
+        match c {
+            '"' => result += "\\\"",
+            '\\' => result += "\\\\",
+            '\n' => result += "\\n",
+            '\r' => result += "\\r",
+            '\t' => result += "\\t",
+            '\u{8}' => result += "\\b",
+            '\u{c}' => result += "\\f",
+            c if c.is_control() => result += &format!("\\u{:04X}", c as u32),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
 impl DescriptionSynth {
     pub(crate) fn text(text: &str) -> Self {
         Self {
             text: text.to_string(),
-            is_multiline: text.contains('\n'),
+            is_multiline: text.contains('\n') && fits_in_block_string(text),
         }
     }
 }
@@ -24,15 +58,18 @@ impl Synth for DescriptionSynth {
             context.write("\"\"\"");
             for line in self.text.split('\n') {
                 context.write_line_jump();
-                context.write(&" ".repeat(context.indent_lvl * context.config.indent_spaces));
-                context.write(&escape_non_escaped_quotes(line));
+                // empty lines are not indented, so that they have no trailing whitespace
+                if !line.is_empty() {
+                    context.write(&" ".repeat(context.indent_lvl * context.config.indent_spaces));
+                    context.write(&line.replace("\"\"\"", "\\\"\"\""));
+                }
             }
             context.write_line_jump();
             context.write(&" ".repeat(context.indent_lvl * context.config.indent_spaces));
             context.write("\"\"\"");
         } else {
             context.write("\"");
-            context.write(&escape_non_escaped_quotes(&self.text));
+            context.write(&escape_string(&self.text));
             context.write("\"");
         }
         true
@@ -42,31 +79,37 @@ impl Synth for DescriptionSynth {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use graphqxl_parser::{parse_spec_with_resolver, BlockEntry, InMemorySourceResolver};
 
-    impl DescriptionSynth {
-        pub(crate) fn text_default(text: &str) -> Self {
-            Self {
-                text: text.to_string(),
-                is_multiline: text.contains('\n'),
-            }
-        }
+    /// Synthesizes `text` as the description of a field and parses it back
+    fn round_trip(text: &str) -> String {
+        let mut context = SynthContext::default();
+        context.push_indent_level();
+        DescriptionSynth::text(text).synth(&mut context);
+        let content = format!("type A {{\n  {}\n  a: Int\n}}", context.result);
+        let resolver = InMemorySourceResolver::new().source("spec.graphqxl", &content);
+        let spec = parse_spec_with_resolver("spec.graphqxl", &resolver).unwrap();
+        let BlockEntry::Field(field) = &spec.types["A"].entries[0] else {
+            panic!("expected a field")
+        };
+        field.description.clone()
     }
 
     #[test]
     fn test_synth_empty() {
-        let synth = DescriptionSynth::text_default("");
+        let synth = DescriptionSynth::text("");
         assert_eq!(synth.synth_zero(), "");
     }
 
     #[test]
     fn test_synth_one_line() {
-        let synth = DescriptionSynth::text_default("This is one line");
+        let synth = DescriptionSynth::text("This is one line");
         assert_eq!(synth.synth_zero(), "\"This is one line\"");
     }
 
     #[test]
     fn test_synth_multiline() {
-        let synth = DescriptionSynth::text_default("These are two lines\nhi!");
+        let synth = DescriptionSynth::text("These are two lines\nhi!");
         assert_eq!(
             synth.synth_zero(),
             "\
@@ -79,7 +122,7 @@ hi!
 
     #[test]
     fn test_synth_multiline_indented() {
-        let synth = DescriptionSynth::text_default("These are two lines\nhi!");
+        let synth = DescriptionSynth::text("These are two lines\nhi!");
         let mut context = SynthContext::default();
         context.push_indent_level();
         synth.synth(&mut context);
@@ -92,4 +135,52 @@ hi!
   \"\"\""
         )
     }
+
+    #[test]
+    fn test_synth_escapes_one_line() {
+        let synth = DescriptionSynth::text("a \"quoted\" \\ text\t");
+        assert_eq!(synth.synth_zero(), r#""a \"quoted\" \\ text\t""#);
+    }
+
+    #[test]
+    fn test_synth_multiline_keeps_indentation_and_blank_lines() {
+        let synth = DescriptionSynth::text("Example:\n\n  query {\n    a\n  }");
+        let mut context = SynthContext::default();
+        context.push_indent_level();
+        synth.synth(&mut context);
+        assert_eq!(
+            context.result,
+            "\"\"\"\n  Example:\n\n    query {\n      a\n    }\n  \"\"\""
+        )
+    }
+
+    #[test]
+    fn test_synth_multiline_escapes_triple_quotes() {
+        let synth = DescriptionSynth::text("a\n\"\"\"b\"\"\"");
+        assert_eq!(synth.synth_zero(), "\"\"\"\na\n\\\"\"\"b\\\"\"\"\n\"\"\"");
+    }
+
+    #[test]
+    fn test_synth_one_line_when_block_string_would_lose_indentation() {
+        let synth = DescriptionSynth::text("  a\n  b");
+        assert_eq!(synth.synth_zero(), r#""  a\n  b""#);
+    }
+
+    #[test]
+    fn test_synth_round_trips_the_parsed_value() {
+        for text in [
+            "one line",
+            "  surrounded by whitespace\t",
+            "Example:\n\n  query {\n    a\n  }",
+            " first line indented\nsecond line  ",
+            "  a\n  b",
+            "\n  leading blank line\n",
+            "with \"\"\" quotes\nand \\\"\"\" escaped ones \"",
+            "blank\n   \nline with spaces",
+            "a\r\nb",
+            "control \u{1}\ncharacter",
+        ] {
+            assert_eq!(round_trip(text), text);
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use graphqxl_parser::{DefType, Spec};
+use graphqxl_parser::{BlockDef, DefType, Spec};
 
 use crate::synth_block_def::BlockDefSynth;
 use crate::synth_directive_def::DirectiveDefSynth;
@@ -9,13 +9,17 @@ use crate::synths::{Synth, SynthContext};
 
 pub(crate) struct SpecSynth(pub(crate) Spec);
 
+fn is_private(def: &BlockDef, context: &SynthContext) -> bool {
+    def.name.id.starts_with(&context.config.private_prefix)
+}
+
 impl Synth for SpecSynth {
     fn synth(&self, context: &mut SynthContext) -> bool {
         for def_name in &self.0.order {
             match def_name {
                 DefType::Type(name) => {
                     let def = self.0.types.get(&name.id).unwrap().to_owned();
-                    if def.generic.is_none() {
+                    if def.generic.is_none() && !is_private(&def, context) {
                         let has_written = BlockDefSynth(def).synth(context);
                         if has_written {
                             context.write_double_line_jump();
@@ -24,7 +28,7 @@ impl Synth for SpecSynth {
                 }
                 DefType::Input(name) => {
                     let def = self.0.inputs.get(&name.id).unwrap().to_owned();
-                    if def.generic.is_none() {
+                    if def.generic.is_none() && !is_private(&def, context) {
                         let has_written = BlockDefSynth(def).synth(context);
                         if has_written {
                             context.write_double_line_jump();
@@ -33,18 +37,24 @@ impl Synth for SpecSynth {
                 }
                 DefType::Enum(name) => {
                     let def = self.0.enums.get(&name.id).unwrap().to_owned();
-                    BlockDefSynth(def).synth(context);
-                    context.write_double_line_jump();
+                    if !is_private(&def, context) {
+                        BlockDefSynth(def).synth(context);
+                        context.write_double_line_jump();
+                    }
                 }
                 DefType::Interface(name) => {
                     let def = self.0.interfaces.get(&name.id).unwrap().to_owned();
-                    BlockDefSynth(def).synth(context);
-                    context.write_double_line_jump();
+                    if def.generic.is_none() && !is_private(&def, context) {
+                        BlockDefSynth(def).synth(context);
+                        context.write_double_line_jump();
+                    }
                 }
                 DefType::Union(name) => {
                     let def = self.0.unions.get(&name.id).unwrap().to_owned();
-                    UnionSynth(def).synth(context);
-                    context.write_double_line_jump();
+                    if def.generic.is_none() {
+                        UnionSynth(def).synth(context);
+                        context.write_double_line_jump();
+                    }
                 }
                 DefType::Scalar(name) => {
                     let def = self.0.scalars.get(&name.id).unwrap().to_owned();
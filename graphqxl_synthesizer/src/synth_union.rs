@@ -35,9 +35,10 @@ impl Synth for UnionSynth {
             v.push(Box::new(StringSynth::from(" ")));
             v.push(Box::new(DirectiveSynth(directive.clone())));
         }
-        if !(self.0.extend && self.0.types.is_empty()) {
+        let types: Vec<Identifier> = self.0.types().cloned().collect();
+        if !(self.0.extend && types.is_empty()) {
             v.push(Box::new(StringSynth::from(" = ")));
-            v.push(Box::new(UnionTypesSynth(self.0.types.clone())));
+            v.push(Box::new(UnionTypesSynth(types)));
         }
 
         let pair_synth = PairSynth::top_level(
@@ -17,11 +17,7 @@ impl Synth for ValueDataSynth {
                     true
                 }
                 ValueBasicData::Float(v) => {
-                    let mut res = v.to_string();
-                    if !res.contains('.') {
-                        res += ".0";
-                    }
-                    context.write(&res);
+                    context.write(v);
                     true
                 }
                 ValueBasicData::Boolean(v) => {
@@ -32,6 +28,18 @@ impl Synth for ValueDataSynth {
                     context.write(&format!("\"{}\"", escape_non_escaped_quotes(v)));
                     true
                 }
+                ValueBasicData::Null => {
+                    context.write("null");
+                    true
+                }
+                ValueBasicData::Enum(v) => {
+                    context.write(v);
+                    true
+                }
+                ValueBasicData::Variable(v) => {
+                    context.write(&format!("${v}"));
+                    true
+                }
             },
             ValueData::List(items) => {
                 context.write("[");
@@ -118,6 +126,25 @@ mod tests {
         assert_eq!(synth.synth_zero(), "false")
     }
 
+    #[test]
+    fn test_float_is_written_as_in_the_source() {
+        let synth = ValueDataSynth(ValueData::build(ValueBasicData::Float(
+            "-1.50E+3".to_string(),
+        )));
+        assert_eq!(synth.synth_zero(), "-1.50E+3")
+    }
+
+    #[test]
+    fn test_null_enum_and_variable() {
+        let synth = ValueDataSynth(
+            ValueData::null()
+                .list()
+                .push(ValueData::enum_value("ADMIN"))
+                .push(ValueData::variable("first")),
+        );
+        assert_eq!(synth.synth_zero(), "[ null, ADMIN, $first ]")
+    }
+
     #[test]
     fn test_list() {
         let synth = ValueDataSynth(ValueData::int(1).list().push(ValueData::int(2)));
@@ -24,10 +24,19 @@ impl Synth for ValueTypeSynth {
                     context.write_with_source("Boolean", span);
                     true
                 }
+                ValueBasicType::Id => {
+                    context.write_with_source("ID", span);
+                    true
+                }
                 ValueBasicType::Object(name) => {
                     IdentifierSynth(name.clone()).synth(context);
                     true
                 }
+                // the transpiler instantiates them, so they are only found in untranspiled specs
+                ValueBasicType::Generic(_) => {
+                    context.write_with_source(&basic.to_string(), span);
+                    true
+                }
             },
             ValueType::NonNullable(value_type, span) => {
                 ValueTypeSynth(*value_type.clone()).synth(context);
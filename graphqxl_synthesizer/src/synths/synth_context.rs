@@ -87,6 +87,7 @@ impl SynthContext {
 
 pub(crate) trait Synth {
     fn synth(&self, context: &mut SynthContext) -> bool;
+    #[cfg(test)]
     fn synth_zero(&self) -> String {
         let mut context = SynthContext::default();
         self.synth(&mut context);
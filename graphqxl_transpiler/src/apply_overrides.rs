@@ -0,0 +1,268 @@
+use crate::utils::{is_subtype, BlockDefStore};
+use graphqxl_parser::{
+    ArgumentDefaultValue, BlockDefType, BlockField, DiagnosticCode, DiagnosticSpan, MultipleErrors,
+    Spec, ValueType,
+};
+
+/// Whether the type of an inherited field can be replaced by `value_type`. Fields of
+/// types and interfaces are read by the clients, so they can only return less values,
+/// while input fields are written by them, so they can only accept more.
+fn is_compatible(
+    value_type: &ValueType,
+    inherited: &ValueType,
+    kind: &BlockDefType,
+    spec: &Spec,
+) -> bool {
+    match kind {
+        BlockDefType::Input => is_subtype(inherited, value_type, spec),
+        _ => is_subtype(value_type, inherited, spec),
+    }
+}
+
+/// Pushes to `errors` the reasons why `field` cannot override `inherited`, which
+/// belongs to a definition of the given `kind`. Returns whether there was any.
+fn check_override(
+    field: &BlockField,
+    inherited: &BlockField,
+    kind: &BlockDefType,
+    spec: &Spec,
+    errors: &mut MultipleErrors,
+) -> bool {
+    let mut failed = false;
+    if let (Some(value_type), Some(expected)) = (&field.value_type, &inherited.value_type) {
+        if !is_compatible(value_type, expected, kind, spec) {
+            let mut err = value_type.span().make_error(
+                DiagnosticCode::InvalidOverride,
+                &format!(
+                    "field \"{}\" cannot be overridden with type {value_type}, as it is not compatible with {expected}",
+                    field.name.id
+                ),
+            );
+            err.secondary(DiagnosticSpan::from(&inherited.span).label("overridden field"));
+            errors.push(err);
+            failed = true;
+        }
+    }
+    // arguments are written by the clients, so they can only accept more values
+    for inherited_arg in inherited.args.iter() {
+        let Some(arg) = field
+            .args
+            .iter()
+            .find(|arg| arg.name.id == inherited_arg.name.id)
+        else {
+            let mut err = field.name.span.make_error(
+                DiagnosticCode::InvalidOverride,
+                &format!(
+                    "field \"{}\" cannot be overridden without its argument \"{}\"",
+                    field.name.id, inherited_arg.name.id
+                ),
+            );
+            err.secondary(DiagnosticSpan::from(&inherited_arg.span).label("overridden argument"));
+            errors.push(err);
+            failed = true;
+            continue;
+        };
+        if !is_subtype(&inherited_arg.value_type, &arg.value_type, spec) {
+            let mut err = arg.value_type.span().make_error(
+                DiagnosticCode::InvalidOverride,
+                &format!(
+                    "argument \"{}\" of field \"{}\" cannot be overridden with type {}, as it does not accept every value of {}",
+                    arg.name.id, field.name.id, arg.value_type, inherited_arg.value_type
+                ),
+            );
+            err.secondary(DiagnosticSpan::from(&inherited_arg.span).label("overridden argument"));
+            errors.push(err);
+            failed = true;
+        }
+    }
+    for arg in field.args.iter() {
+        let is_new = !inherited
+            .args
+            .iter()
+            .any(|inherited_arg| inherited_arg.name.id == arg.name.id);
+        let is_required = matches!(arg.value_type, ValueType::NonNullable(_, _))
+            && arg.default == ArgumentDefaultValue::None;
+        if is_new && is_required {
+            errors.push(arg.span.make_error(
+                DiagnosticCode::InvalidOverride,
+                &format!(
+                    "argument \"{}\" of field \"{}\" cannot be required, as the overridden field does not have it",
+                    arg.name.id, field.name.id
+                ),
+            ));
+            failed = true;
+        }
+    }
+    failed
+}
+
+/// Replaces the fields that come from spreads with the fields marked with `override`
+/// that have the same name, keeping the position of the replaced ones. `is_inherited`
+/// tells if an entry comes from a spread, only those ones can be overridden, and `kind`
+/// is the kind of the definition that declares the overrides.
+pub(crate) fn apply_overrides<T>(
+    entries: Vec<(BlockField, T)>,
+    kind: &BlockDefType,
+    store: &BlockDefStore,
+    is_inherited: impl Fn(&T) -> bool,
+    errors: &mut MultipleErrors,
+) -> Vec<(BlockField, T)> {
+    let (overrides, mut entries): (Vec<_>, Vec<_>) =
+        entries.into_iter().partition(|(field, _)| field.overrides);
+
+    let empty = Spec::default();
+    let spec = store.spec().unwrap_or(&empty);
+    for (field, origin) in overrides {
+        let Some((inherited, inherited_origin)) = entries
+            .iter_mut()
+            .find(|(inherited, origin)| inherited.name.id == field.name.id && is_inherited(origin))
+        else {
+            errors.push(field.name.span.make_error(
+                DiagnosticCode::InvalidOverride,
+                &format!(
+                    "field \"{}\" does not override any field included by a spread",
+                    field.name.id
+                ),
+            ));
+            continue;
+        };
+        if check_override(&field, inherited, kind, spec, errors) {
+            continue;
+        }
+        let mut overridden = field;
+        overridden.overrides = false;
+        if overridden.description.is_empty() {
+            overridden.description = inherited.description.clone();
+        }
+        *inherited = overridden;
+        *inherited_origin = origin;
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{errors, fields, transpile};
+    use graphqxl_parser::DiagnosticCode;
+
+    #[test]
+    fn test_replaces_field_at_its_position() {
+        let spec = transpile(
+            r#"
+type Base { "The id" id: ID name: String }
+type User { ...Base override id: ID! age: Int }
+"#,
+        )
+        .unwrap();
+        let fields = fields(&spec.types["User"]);
+        let names: Vec<&str> = fields.iter().map(|f| f.name.id.as_str()).collect();
+        assert_eq!(names, vec!["id", "name", "age"]);
+        assert_eq!(fields[0].value_type.as_ref().unwrap().to_string(), "ID!");
+        assert_eq!(fields[0].description, "The id");
+        assert!(!fields[0].overrides);
+    }
+
+    #[test]
+    fn test_overrides_through_nested_spreads() {
+        let spec = transpile(
+            "
+interface Node { id: ID }
+type Image implements Node { id: ID }
+type _Base { id: ID node: Node }
+type _Middle { ..._Base }
+type User { ..._Middle override node: Image }
+",
+        )
+        .unwrap();
+        let fields = fields(&spec.types["User"]);
+        assert_eq!(fields[1].value_type.as_ref().unwrap().to_string(), "Image");
+    }
+
+    #[test]
+    fn test_overrides_with_union_members() {
+        let spec = transpile(
+            "
+type Book { title: String } type Movie { title: String }
+union SearchResult = Book
+extend union SearchResult = Movie
+type _Base { result: SearchResult }
+type Page { ..._Base override result: Movie }
+",
+        )
+        .unwrap();
+        let fields = fields(&spec.types["Page"]);
+        assert_eq!(fields[0].value_type.as_ref().unwrap().to_string(), "Movie");
+    }
+
+    #[test]
+    fn test_incompatible_override_should_fail() {
+        let errors = errors("type _Base { id: ID! } type User { ..._Base override id: ID }");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, DiagnosticCode::InvalidOverride);
+        assert_eq!(
+            errors[0].message,
+            "field \"id\" cannot be overridden with type ID, as it is not compatible with ID!"
+        );
+        assert_eq!(errors[0].secondary.len(), 1);
+    }
+
+    #[test]
+    fn test_override_without_inherited_field_should_fail() {
+        let errors = errors("type _Base { id: ID } type User { ..._Base override name: String }");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].message,
+            "field \"name\" does not override any field included by a spread"
+        );
+    }
+
+    #[test]
+    fn test_input_overrides_can_only_accept_more_values() {
+        let spec =
+            transpile("input _Base { a: String! } input Filter { ..._Base override a: String }")
+                .unwrap();
+        let fields = fields(&spec.inputs["Filter"]);
+        assert_eq!(fields[0].value_type.as_ref().unwrap().to_string(), "String");
+
+        let errors =
+            errors("input _Base { a: String } input Filter { ..._Base override a: String! }");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].message,
+            "field \"a\" cannot be overridden with type String!, as it is not compatible with String"
+        );
+    }
+
+    #[test]
+    fn test_overrides_can_widen_arguments() {
+        let spec = transpile(
+            "type _Base { a(x: Int!): Int } type A { ..._Base override a(x: Int, y: Int): Int! }",
+        )
+        .unwrap();
+        let fields = fields(&spec.types["A"]);
+        assert_eq!(fields[0].args.len(), 2);
+        assert_eq!(fields[0].args[0].value_type.to_string(), "Int");
+    }
+
+    #[test]
+    fn test_overrides_that_drop_or_narrow_arguments_should_fail() {
+        let errors = errors(
+            "
+type _Base { a(x: Int, y: Int): Int }
+type A { ..._Base override a(x: Int!, z: Int!): Int }
+",
+        );
+        let messages: Vec<&str> = errors.iter().map(|err| err.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "argument \"x\" of field \"a\" cannot be overridden with type Int!, as it does not accept every value of Int",
+                "field \"a\" cannot be overridden without its argument \"y\"",
+                "argument \"z\" of field \"a\" cannot be required, as the overridden field does not have it",
+            ]
+        );
+        assert!(errors
+            .iter()
+            .all(|err| err.code == DiagnosticCode::InvalidOverride));
+    }
+}
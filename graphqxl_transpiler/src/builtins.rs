@@ -0,0 +1,131 @@
+use crate::tree_shake::References;
+use crate::utils::{definition_kind, retain_order};
+use graphqxl_parser::{parse_spec_with_resolver, DefType, InMemorySourceResolver, Spec};
+use std::collections::HashSet;
+
+const BUILTINS_FILE: &str = "builtins.graphqxl";
+
+/// Relay's `Node`, `PageInfo`, `Edge<T>` and `Connection<T>`, so that specs can use
+/// them without declaring the same boilerplate over and over again
+const BUILTINS: &str = include_str!("builtins.graphqxl");
+
+fn builtin_spec() -> Spec {
+    let resolver = InMemorySourceResolver::new().source(BUILTINS_FILE, BUILTINS);
+    parse_spec_with_resolver(BUILTINS_FILE, &resolver).expect("built-in definitions are valid")
+}
+
+/// Copies `spec` adding the built-in definitions whose names it does not define, so
+/// that the ones of the user always take precedence. Also returns the added names.
+pub(crate) fn with_builtins(spec: &Spec) -> (Spec, HashSet<String>) {
+    let builtins = builtin_spec();
+    let mut spec = spec.clone();
+    let mut added = HashSet::new();
+    for def in builtins.order.iter() {
+        let name = match def {
+            DefType::Type(name) | DefType::Interface(name) => name,
+            _ => continue,
+        };
+        if definition_kind(&spec, &name.id).is_some() {
+            continue;
+        }
+        match def {
+            DefType::Type(_) => spec
+                .types
+                .insert(name.id.clone(), builtins.types[&name.id].clone()),
+            _ => spec
+                .interfaces
+                .insert(name.id.clone(), builtins.interfaces[&name.id].clone()),
+        };
+        spec.order.push(def.clone());
+        added.insert(name.id.clone());
+    }
+    (spec, added)
+}
+
+/// Removes the `builtins` that nothing else in the transpiled `spec` references, as
+/// they were not declared by the user
+pub(crate) fn remove_unused_builtins(mut spec: Spec, builtins: &HashSet<String>) -> Spec {
+    loop {
+        let mut referenced: HashSet<String> = HashSet::new();
+        for block_def in spec
+            .types
+            .values()
+            .chain(spec.inputs.values())
+            .chain(spec.enums.values())
+            .chain(spec.interfaces.values())
+        {
+            let mut references = References::default();
+            references.block_def(block_def);
+            referenced.extend(
+                references
+                    .defs
+                    .into_iter()
+                    .filter(|name| *name != block_def.name.id)
+                    .map(|name| name.to_string()),
+            );
+        }
+        for union in spec.unions.values() {
+            referenced.extend(union.types().map(|member| member.id.clone()));
+        }
+        let unused: HashSet<&String> = builtins
+            .iter()
+            .filter(|name| !referenced.contains(*name))
+            .filter(|name| spec.types.contains_key(*name) || spec.interfaces.contains_key(*name))
+            .collect();
+        if unused.is_empty() {
+            break;
+        }
+        spec.types.retain(|name, _| !unused.contains(name));
+        spec.interfaces.retain(|name, _| !unused.contains(name));
+    }
+    retain_order(&mut spec);
+    spec
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{field_names, fields, transpile};
+    use graphqxl_parser::{Identifier, ValueType};
+
+    #[test]
+    fn test_instantiates_builtin_generics() {
+        let spec = transpile(
+            "
+type User implements Node { id: ID! }
+type UserConnection = Connection<User!>
+type Query { users: UserConnection! }
+",
+        )
+        .unwrap();
+        assert_eq!(field_names(&spec.types["UserEdge"]), vec!["cursor", "node"]);
+        assert_eq!(
+            fields(&spec.types["UserConnection"])[0].value_type,
+            Some(ValueType::object(Identifier::from("UserEdge")).array())
+        );
+        assert_eq!(
+            field_names(&spec.types["UserConnection"]),
+            vec!["edges", "pageInfo"]
+        );
+        assert!(spec.types.contains_key("PageInfo"));
+        assert!(spec.interfaces.contains_key("Node"));
+    }
+
+    #[test]
+    fn test_unused_builtins_are_not_generated() {
+        let spec = transpile("type Query { a: Int }").unwrap();
+        assert_eq!(spec.types.len(), 1);
+        assert!(spec.interfaces.is_empty());
+    }
+
+    #[test]
+    fn test_user_definitions_take_precedence() {
+        let spec = transpile(
+            "
+type PageInfo { hasNextPage: Boolean! }
+type UserConnection = Connection<String>
+",
+        )
+        .unwrap();
+        assert_eq!(field_names(&spec.types["PageInfo"]), vec!["hasNextPage"]);
+    }
+}
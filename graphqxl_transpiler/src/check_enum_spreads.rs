@@ -0,0 +1,124 @@
+use crate::utils::definition_kind;
+use graphqxl_parser::{BlockDef, BlockEntry, DiagnosticCode, ModifiedRef, MultipleErrors, Spec};
+use std::error::Error;
+
+fn check_enum_spread(spec: &Spec, modified_ref: &ModifiedRef, errors: &mut MultipleErrors) {
+    match modified_ref {
+        ModifiedRef::Required(_, span) | ModifiedRef::Optional(_, span) => {
+            errors.push(span.make_error(
+                DiagnosticCode::InvalidType,
+                "enum values have no type, so they cannot be made required or optional",
+            ));
+        }
+        ModifiedRef::Omit(inner, _, _) | ModifiedRef::Pick(inner, _, _) => {
+            check_enum_spread(spec, inner, errors)
+        }
+        ModifiedRef::ExpandableRef(expandable_ref) => {
+            let name = &expandable_ref.identifier;
+            if spec.enums.contains_key(&name.id) {
+                return;
+            }
+            if let Some(kind) = definition_kind(spec, &name.id) {
+                errors.push(name.span.make_error(
+                    DiagnosticCode::InvalidType,
+                    &format!(
+                        "{} is {kind}, only enums can be spread into an enum",
+                        name.id
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Enums can only be composed out of other enums, and their values cannot be
+/// modified with `Required` or `Optional`, only filtered with `Omit` or `Pick`
+pub(crate) fn check_enum_spreads(spec: &Spec, enum_def: &BlockDef) -> Result<(), Box<dyn Error>> {
+    let mut errors = MultipleErrors::new();
+    for entry in enum_def.entries.iter() {
+        if let BlockEntry::SpreadRef(modified_ref) = entry {
+            check_enum_spread(spec, modified_ref, &mut errors);
+        }
+    }
+    errors.into_result(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{errors, field_names, transpile};
+    use graphqxl_parser::{BlockEntry, DiagnosticCode};
+
+    #[test]
+    fn test_composes_and_filters_enums() {
+        let spec = transpile(
+            r#"
+enum Role { "Can do anything" ADMIN @deprecated USER GUEST }
+enum AdminRole { ...Role SUPERADMIN }
+enum RegisteredRole { ...Omit<Role, "GUEST"> }
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            field_names(&spec.enums["AdminRole"]),
+            vec!["ADMIN", "USER", "GUEST", "SUPERADMIN"]
+        );
+        assert_eq!(
+            field_names(&spec.enums["RegisteredRole"]),
+            vec!["ADMIN", "USER"]
+        );
+        let BlockEntry::Field(admin) = &spec.enums["RegisteredRole"].entries[0] else {
+            panic!("expected a value")
+        };
+        assert_eq!(admin.description, "Can do anything");
+        assert_eq!(admin.directives[0].name.id, "deprecated");
+    }
+
+    #[test]
+    fn test_includes_values_of_extensions() {
+        let spec = transpile(
+            r#"
+enum Role { ADMIN USER }
+extend enum Role { GUEST }
+enum AllRoles { ...Role }
+enum RegisteredRole { ...Omit<Role, "GUEST"> }
+enum GuestRole { ...Pick<Role, "GUEST"> }
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            field_names(&spec.enums["AllRoles"]),
+            vec!["ADMIN", "USER", "GUEST"]
+        );
+        assert_eq!(
+            field_names(&spec.enums["RegisteredRole"]),
+            vec!["ADMIN", "USER"]
+        );
+        assert_eq!(field_names(&spec.enums["GuestRole"]), vec!["GUEST"]);
+    }
+
+    #[test]
+    fn test_enum_values_cannot_be_required_or_optional() {
+        let errors =
+            errors("enum Role { A } enum X { ...Required<Role> } enum Y { ...Optional<Role> }");
+        assert_eq!(errors.len(), 2);
+        assert!(errors
+            .iter()
+            .all(|err| err.code == DiagnosticCode::InvalidType));
+    }
+
+    #[test]
+    fn test_only_enums_can_be_spread_into_enums() {
+        let errors = errors("type T { a: Int } enum X { ...Omit<T, \"a\"> }");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].message,
+            "T is a type, only enums can be spread into an enum"
+        );
+    }
+
+    #[test]
+    fn test_omitting_unknown_value_should_fail() {
+        let errors = errors("enum Role { A } enum X { ...Omit<Role, \"B\"> }");
+        assert_eq!(errors[0].message, "value \"B\" does not exist");
+    }
+}
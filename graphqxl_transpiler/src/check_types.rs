@@ -0,0 +1,386 @@
+use crate::strip_private::private_definitions;
+use crate::transpile_block_def::{nested_spreads, spread_chain};
+use crate::utils::{definition_kind, BlockDefStore};
+use graphqxl_parser::{
+    Argument, BlockDef, BlockEntry, DefType, Diagnostic, DiagnosticCode, DiagnosticSpan,
+    Identifier, MultipleErrors, OwnedSpan, Spec, ValueBasicType, ValueType,
+};
+use std::collections::HashSet;
+use std::error::Error;
+
+#[derive(Clone, Copy)]
+enum Position {
+    Input,
+    Output,
+    UnionMember,
+    Implements,
+    SchemaRoot,
+}
+
+impl Position {
+    fn allows(&self, spec: &Spec, name: &str) -> bool {
+        let is_leaf = spec.enums.contains_key(name) || spec.scalars.contains_key(name);
+        match self {
+            Position::Input => is_leaf || spec.inputs.contains_key(name),
+            Position::Output => {
+                is_leaf
+                    || spec.types.contains_key(name)
+                    || spec.interfaces.contains_key(name)
+                    || spec.unions.contains_key(name)
+            }
+            Position::UnionMember | Position::SchemaRoot => spec.types.contains_key(name),
+            Position::Implements => spec.interfaces.contains_key(name),
+        }
+    }
+
+    fn restriction(&self) -> &'static str {
+        match self {
+            Position::Input => "it cannot be used as an input type",
+            Position::Output => "it cannot be used as an output type",
+            Position::UnionMember => "only types can be members of a union",
+            Position::Implements => "only interfaces can be implemented",
+            Position::SchemaRoot => "only types can be schema roots",
+        }
+    }
+}
+
+struct TypeChecker<'a> {
+    /// The transpiled spec, in which the references are checked
+    spec: &'a Spec,
+    /// The spec before transpiling it, to explain why a definition is missing
+    source: &'a Spec,
+    /// The source blocks, to explain where the fields of a transpiled block come from
+    source_store: BlockDefStore<'a>,
+    private_prefix: &'a str,
+    /// The source definitions marked with `@private`, which are not transpiled either
+    private: HashSet<String>,
+    errors: MultipleErrors,
+}
+
+impl<'a> TypeChecker<'a> {
+    fn check_name(&self, identifier: &Identifier, position: Position) -> Option<Box<Diagnostic>> {
+        let name = &identifier.id;
+        let Some(kind) = definition_kind(self.spec, name) else {
+            let message = if definition_kind(self.source, name).is_none() {
+                format!("{name} is undefined")
+            } else if (!self.private_prefix.is_empty() && name.starts_with(self.private_prefix))
+                || self.private.contains(name)
+            {
+                format!("{name} is private, so it is not part of the generated schema")
+            } else {
+                format!("{name} is generic, so it needs to be instantiated before using it")
+            };
+            return Some(
+                identifier
+                    .span
+                    .make_error(DiagnosticCode::UndefinedReference, &message),
+            );
+        };
+        if position.allows(self.spec, name) {
+            return None;
+        }
+        Some(identifier.span.make_error(
+            DiagnosticCode::InvalidType,
+            &format!("{name} is {kind}, {}", position.restriction()),
+        ))
+    }
+
+    fn check_value_type(
+        &self,
+        value_type: &ValueType,
+        position: Position,
+    ) -> Option<Box<Diagnostic>> {
+        match value_type.retrieve_basic_type() {
+            ValueBasicType::Object(identifier) => self.check_name(identifier, position),
+            _ => None,
+        }
+    }
+
+    fn check_args<'b>(
+        &'b self,
+        args: &'b [Argument],
+    ) -> impl Iterator<Item = Box<Diagnostic>> + 'b {
+        args.iter()
+            .filter_map(|arg| self.check_value_type(&arg.value_type, Position::Input))
+    }
+
+    /// Spreads, or generic instantiation, that brought `field` into the block stored
+    /// under `key`. It is empty if the block declares the field by itself.
+    fn field_spreads(&self, key: &str, field: &str) -> Vec<OwnedSpan> {
+        let source = self.source;
+        if let Some(block_def) = self.source_store.get(key) {
+            for entry in block_def.entries.iter() {
+                match entry {
+                    BlockEntry::Field(block_field) if block_field.name.id == field => break,
+                    BlockEntry::SpreadRef(spread)
+                        if nested_spreads(spread, field, &self.source_store, 0).is_some() =>
+                    {
+                        return spread_chain(spread, field, &self.source_store);
+                    }
+                    _ => {}
+                }
+            }
+        } else if let Some(generic) = source
+            .generic_types
+            .get(key)
+            .or_else(|| source.generic_inputs.get(key))
+            .or_else(|| source.generic_interfaces.get(key))
+        {
+            return spread_chain(&generic.modified_ref, field, &self.source_store);
+        }
+        vec![]
+    }
+
+    fn check_block_def(&mut self, key: &str, block_def: &BlockDef, position: Position) {
+        for interface in block_def
+            .implements
+            .iter()
+            .flat_map(|i| i.interfaces.iter())
+        {
+            if let Some(err) = self.check_name(interface, Position::Implements) {
+                self.errors.push(err);
+            }
+        }
+        for entry in block_def.entries.iter() {
+            let BlockEntry::Field(field) = entry else {
+                continue;
+            };
+            let name = &field.name.id;
+            let mut errors = vec![];
+            if let Some(value_type) = &field.value_type {
+                errors.extend(self.check_value_type(value_type, position));
+            }
+            if matches!(position, Position::Input) && !field.args.is_empty() {
+                errors.push(field.name.span.make_error(
+                    DiagnosticCode::InvalidType,
+                    &format!("field {name} has arguments, but input fields cannot have them"),
+                ));
+            }
+            errors.extend(self.check_args(&field.args));
+            if errors.is_empty() {
+                continue;
+            }
+            let spreads = self.field_spreads(key, name);
+            for mut err in errors {
+                for (i, span) in spreads.iter().enumerate() {
+                    let label = if i == 0 {
+                        format!("\"{name}\" is included by this spread")
+                    } else {
+                        "through this spread".to_string()
+                    };
+                    err.secondary(DiagnosticSpan::from(span).label(&label));
+                }
+                self.errors.push(err);
+            }
+        }
+    }
+
+    fn check(&mut self) {
+        let spec = self.spec;
+        for def in spec.order.iter() {
+            match def {
+                DefType::Type(name) => {
+                    self.check_block_def(&name.id, &spec.types[&name.id], Position::Output)
+                }
+                DefType::Interface(name) => {
+                    self.check_block_def(&name.id, &spec.interfaces[&name.id], Position::Output)
+                }
+                DefType::Input(name) => {
+                    self.check_block_def(&name.id, &spec.inputs[&name.id], Position::Input)
+                }
+                DefType::Union(name) => {
+                    for member in spec.unions[&name.id].types() {
+                        if let Some(err) = self.check_name(member, Position::UnionMember) {
+                            self.errors.push(err);
+                        }
+                    }
+                }
+                DefType::Directive(name) => {
+                    let errors: Vec<_> = self
+                        .check_args(&spec.directives[&name.id].arguments)
+                        .collect();
+                    for err in errors {
+                        self.errors.push(err);
+                    }
+                }
+                DefType::Schema(key) => {
+                    let schema = &spec.schemas[key];
+                    for root in [&schema.query, &schema.mutation, &schema.subscription] {
+                        if root.id.is_empty() {
+                            continue;
+                        }
+                        if let Some(err) = self.check_name(root, Position::SchemaRoot) {
+                            self.errors.push(err);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Checks that every type referenced in the transpiled `spec` is defined, and that it
+/// can be used where it is referenced: inputs as arguments and input fields, types,
+/// interfaces and unions as fields of types and interfaces, and enums and scalars in both.
+/// Errors about fields brought by spreads also point to the spreads that brought them.
+pub(crate) fn check_types(
+    spec: &Spec,
+    source: &Spec,
+    private_prefix: &str,
+) -> Result<(), Box<dyn Error>> {
+    let mut checker = TypeChecker {
+        spec,
+        source,
+        source_store: BlockDefStore::from(vec![&source.types, &source.inputs, &source.interfaces]),
+        private_prefix,
+        private: private_definitions(source),
+        errors: MultipleErrors::new(),
+    };
+    checker.check();
+    checker.errors.into_result(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::errors;
+    use graphqxl_parser::DiagnosticCode;
+
+    #[test]
+    fn test_accepts_valid_references() {
+        let errors = errors(
+            "
+scalar Date
+enum Role { ADMIN }
+interface Node { id: ID! }
+input Filter { role: Role from: Date }
+type User implements Node { id: ID! role: Role friends(filter: Filter): [User!] }
+union Result = User
+type Query { search(filter: Filter): Result }
+directive @limit(role: Role) on FIELD_DEFINITION
+",
+        );
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    #[test]
+    fn test_undefined_types_should_fail() {
+        let errors = errors("type Query { a: Missing b(arg: [Other!]): Int }");
+        assert_eq!(errors.len(), 2);
+        assert!(errors
+            .iter()
+            .all(|err| err.code == DiagnosticCode::UndefinedReference));
+        assert_eq!(errors[0].message, "Missing is undefined");
+        assert_eq!(errors[0].primary.as_ref().unwrap().col, 17);
+    }
+
+    #[test]
+    fn test_private_types_cannot_be_referenced() {
+        let errors = errors("type _Base { a: Int } type Query { a: _Base }");
+        assert_eq!(
+            errors[0].message,
+            "_Base is private, so it is not part of the generated schema"
+        );
+    }
+
+    #[test]
+    fn test_types_marked_as_private_cannot_be_referenced() {
+        let errors = errors("type Secret @private { a: Int } type Query { s: Secret }");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, DiagnosticCode::UndefinedReference);
+        assert_eq!(
+            errors[0].message,
+            "Secret is private, so it is not part of the generated schema"
+        );
+    }
+
+    #[test]
+    fn test_private_fields_can_reference_private_types() {
+        let errors =
+            errors("type Secret @private { a: Int } type Query { a: Int s: Secret @private }");
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    #[test]
+    fn test_types_in_the_wrong_position_should_fail() {
+        let errors = errors(
+            "
+type User { a: Int }
+input Filter { a: Int }
+interface Node { id: ID }
+type Query { a: Filter b(filter: User): Int }
+input Other { user: User }
+union Result = User | Filter
+type Impl implements User { a: Int }
+",
+        );
+        let messages: Vec<&str> = errors.iter().map(|err| err.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Filter is an input, it cannot be used as an output type",
+                "User is a type, it cannot be used as an input type",
+                "User is a type, it cannot be used as an input type",
+                "Filter is an input, only types can be members of a union",
+                "User is a type, only interfaces can be implemented",
+            ]
+        );
+        assert!(errors
+            .iter()
+            .all(|err| err.code == DiagnosticCode::InvalidType));
+    }
+
+    #[test]
+    fn test_points_to_the_spreads_that_bring_a_field_in_the_wrong_position() {
+        let errors = errors(
+            "
+input Filter { a: Int }
+type _Base { filter: Filter }
+type _Other { ..._Base }
+type Query { ..._Other }
+",
+        );
+        assert_eq!(errors.len(), 1);
+        let err = &errors[0];
+        assert_eq!(
+            err.message,
+            "Filter is an input, it cannot be used as an output type"
+        );
+        assert_eq!(err.primary.as_ref().unwrap().line, 3);
+        let secondary: Vec<(usize, Option<&str>)> = err
+            .secondary
+            .iter()
+            .map(|span| (span.line, span.label.as_deref()))
+            .collect();
+        assert_eq!(
+            secondary,
+            vec![
+                (5, Some("\"filter\" is included by this spread")),
+                (4, Some("through this spread")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fields_with_args_cannot_be_spread_into_inputs() {
+        let errors = errors(
+            "
+type User { name(upper: Boolean): String }
+input UserInput { ...User }
+input Other = User
+",
+        );
+        let messages: Vec<&str> = errors.iter().map(|err| err.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "field name has arguments, but input fields cannot have them",
+                "field name has arguments, but input fields cannot have them",
+            ]
+        );
+        for (err, line) in errors.iter().zip([3, 4]) {
+            assert_eq!(err.primary.as_ref().unwrap().line, 2);
+            assert_eq!(err.secondary[0].line, line);
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use crate::resolve_modified_ref::MAX_RECURSION_DEPTH;
+use crate::strip_private::without_private;
+use crate::utils::BlockDefStore;
+use graphqxl_parser::{BlockDef, BlockEntry, Directive, DirectiveDef};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Which of the block level directives of a definition are inherited by the
+/// definitions that reuse it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DirectiveInheritance {
+    None,
+    All,
+    Only(Vec<String>),
+}
+
+impl DirectiveInheritance {
+    fn inherits(&self, name: &str) -> bool {
+        match self {
+            DirectiveInheritance::None => false,
+            DirectiveInheritance::All => true,
+            DirectiveInheritance::Only(names) => names.iter().any(|n| n == name),
+        }
+    }
+}
+
+impl FromStr for DirectiveInheritance {
+    type Err = String;
+
+    /// Parses `none`, `all` or a comma separated list of directive names
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "none" => Ok(DirectiveInheritance::None),
+            "all" => Ok(DirectiveInheritance::All),
+            names => {
+                let names: Vec<String> = names
+                    .split(',')
+                    .map(|name| name.trim().trim_start_matches('@').to_string())
+                    .collect();
+                if names.iter().any(|name| name.is_empty()) {
+                    return Err(format!(
+                        "expected \"none\", \"all\" or a comma separated list of directive names, got \"{s}\""
+                    ));
+                }
+                Ok(DirectiveInheritance::Only(names))
+            }
+        }
+    }
+}
+
+impl Display for DirectiveInheritance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DirectiveInheritance::None => write!(f, "none"),
+            DirectiveInheritance::All => write!(f, "all"),
+            DirectiveInheritance::Only(names) => write!(f, "{}", names.join(",")),
+        }
+    }
+}
+
+pub(crate) struct DirectiveRules<'a> {
+    pub(crate) spreads: &'a DirectiveInheritance,
+    pub(crate) generics: &'a DirectiveInheritance,
+    pub(crate) definitions: &'a HashMap<String, DirectiveDef>,
+}
+
+impl<'a> DirectiveRules<'a> {
+    /// Whether `directive` can be left out because `directives` already has it. Non
+    /// repeatable directives can only be used once, so the first one wins, the other
+    /// ones are only dropped if they are exactly the same.
+    fn is_duplicated(&self, directive: &Directive, directives: &[Directive]) -> bool {
+        let repeatable = self
+            .definitions
+            .get(&directive.name.id)
+            .map(|def| def.is_repeatable)
+            .unwrap_or(true);
+        directives.iter().any(|other| {
+            other.name.id == directive.name.id && (!repeatable || other.call == directive.call)
+        })
+    }
+
+    /// Places the `inherited` directives that are not already present before `own`
+    fn merge(&self, own: Vec<Directive>, inherited: Vec<Directive>) -> Vec<Directive> {
+        let mut merged: Vec<Directive> = vec![];
+        for directive in inherited {
+            if !self.is_duplicated(&directive, &own) && !self.is_duplicated(&directive, &merged) {
+                merged.push(directive);
+            }
+        }
+        merged.extend(own);
+        merged
+    }
+
+    /// The directives that the spreads of `block_def` bring into it, following the
+    /// spreads of the spread definitions
+    fn spread_directives(
+        &self,
+        block_def: &BlockDef,
+        store: &BlockDefStore,
+        depth: usize,
+    ) -> Vec<Directive> {
+        let mut inherited = vec![];
+        if depth > MAX_RECURSION_DEPTH || *self.spreads == DirectiveInheritance::None {
+            return inherited;
+        }
+        for entry in block_def.entries.iter() {
+            let BlockEntry::SpreadRef(modified_ref) = entry else {
+                continue;
+            };
+            let Some(source) = store.get(&modified_ref.identifier().id) else {
+                continue;
+            };
+            let directives = self.of(source, store, depth + 1);
+            inherited.extend(
+                directives
+                    .into_iter()
+                    .filter(|directive| self.spreads.inherits(&directive.name.id)),
+            );
+        }
+        inherited
+    }
+
+    /// The directives of `block_def` including the ones inherited through its spreads
+    pub(crate) fn of(
+        &self,
+        block_def: &BlockDef,
+        store: &BlockDefStore,
+        depth: usize,
+    ) -> Vec<Directive> {
+        let inherited = self.spread_directives(block_def, store, depth);
+        self.merge(without_private(block_def.directives.clone()), inherited)
+    }
+
+    /// Adds to `transpiled` the directives inherited through the spreads of `block_def`
+    pub(crate) fn inherit_from_spreads(
+        &self,
+        transpiled: &mut BlockDef,
+        block_def: &BlockDef,
+        store: &BlockDefStore,
+    ) {
+        let inherited = self.spread_directives(block_def, store, 0);
+        let own = std::mem::take(&mut transpiled.directives);
+        transpiled.directives = self.merge(own, inherited);
+    }
+
+    /// The directives of a generic instantiation that has `own` ones, of a definition
+    /// that has the `referenced` ones
+    pub(crate) fn inherit_from_generic(
+        &self,
+        own: Vec<Directive>,
+        referenced: Vec<Directive>,
+    ) -> Vec<Directive> {
+        let inherited = without_private(referenced)
+            .into_iter()
+            .filter(|directive| self.generics.inherits(&directive.name.id))
+            .collect();
+        self.merge(own, inherited)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{test_options, transpile_with};
+    use crate::{DirectiveInheritance, TranspileSpecOptions};
+    use graphqxl_parser::{Directive, Spec, ValueBasicData, ValueData};
+
+    const DIRECTIVES: &str = "
+directive @key(fields: String!) repeatable on OBJECT | INTERFACE
+directive @auth(role: String) on OBJECT | INTERFACE | INPUT_OBJECT
+directive @other on OBJECT
+";
+
+    fn transpile(content: &str, spreads: &str, generics: &str) -> Spec {
+        let options = TranspileSpecOptions {
+            spread_directives: spreads.parse().unwrap(),
+            generic_directives: generics.parse().unwrap(),
+            ..test_options()
+        };
+        transpile_with(&format!("{DIRECTIVES}{content}"), &options).unwrap()
+    }
+
+    fn directives(spec: &Spec, name: &str) -> Vec<String> {
+        spec.types[name]
+            .directives
+            .iter()
+            .map(|directive| directive.name.id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_parses_inheritance() {
+        assert_eq!("none".parse(), Ok(DirectiveInheritance::None));
+        assert_eq!("all".parse(), Ok(DirectiveInheritance::All));
+        assert_eq!(
+            "key, @auth".parse(),
+            Ok(DirectiveInheritance::Only(vec![
+                "key".to_string(),
+                "auth".to_string()
+            ]))
+        );
+        assert!("key,,auth".parse::<DirectiveInheritance>().is_err());
+    }
+
+    #[test]
+    fn test_spreads_do_not_inherit_by_default() {
+        let spec = transpile(
+            "type _Base @key(fields: \"id\") { id: ID! } type A { ..._Base }",
+            "none",
+            "all",
+        );
+        assert!(directives(&spec, "A").is_empty());
+    }
+
+    #[test]
+    fn test_spreads_inherit_selected_directives_transitively() {
+        let spec = transpile(
+            r#"
+type _Node @key(fields: "id") @other @private { id: ID! }
+type _Base @auth(role: "user") { ..._Node }
+type A { ...Omit<_Base, "id"> name: String }
+"#,
+            "key,auth",
+            "all",
+        );
+        assert_eq!(directives(&spec, "A"), vec!["key", "auth"]);
+    }
+
+    #[test]
+    fn test_deduplicates_by_repeatable() {
+        let spec = transpile(
+            r#"
+type _A @key(fields: "id") @auth(role: "user") { id: ID! }
+type _B @key(fields: "id") @key(fields: "email") @auth(role: "admin") { email: String }
+type C @auth(role: "owner") { ..._A ..._B }
+"#,
+            "all",
+            "all",
+        );
+        assert_eq!(directives(&spec, "C"), vec!["key", "key", "auth"]);
+        let auth = Directive::build("auth").input(
+            "role",
+            ValueData::build(ValueBasicData::String("owner".to_string())),
+        );
+        assert_eq!(spec.types["C"].directives[2], auth);
+    }
+
+    #[test]
+    fn test_generics_follow_their_own_rule() {
+        let spec = transpile(
+            r#"
+type Page<T> @key(fields: "id") @auth(role: "user") { id: ID! items: [T] }
+type UserPage @auth(role: "admin") = Page<String>
+type OtherPage = Page<Int>
+"#,
+            "none",
+            "key",
+        );
+        assert_eq!(directives(&spec, "UserPage"), vec!["key", "auth"]);
+        assert_eq!(directives(&spec, "OtherPage"), vec!["key"]);
+    }
+}
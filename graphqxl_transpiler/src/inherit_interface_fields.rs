@@ -0,0 +1,270 @@
+use crate::utils::is_subtype;
+use graphqxl_parser::{
+    BlockDef, BlockEntry, BlockField, DiagnosticCode, DiagnosticSpan, MultipleErrors, Spec,
+};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+
+fn fields(block_def: &BlockDef) -> impl Iterator<Item = &BlockField> {
+    block_def.entries.iter().filter_map(|entry| match entry {
+        BlockEntry::Field(field) => Some(field.as_ref()),
+        BlockEntry::SpreadRef(_) => None,
+    })
+}
+
+/// All the fields of the definition called `name`, including its extensions
+fn all_fields<'a>(blocks: &'a HashMap<String, BlockDef>, name: &str) -> Vec<&'a BlockField> {
+    blocks
+        .values()
+        .filter(|block_def| block_def.name.id == name)
+        .flat_map(fields)
+        .collect()
+}
+
+fn type_name(field: &BlockField) -> String {
+    match &field.value_type {
+        Some(value_type) => value_type.to_string(),
+        None => "no type".to_string(),
+    }
+}
+
+/// Adds to the definition stored at `key` the fields of the interfaces it implements
+/// that it does not declare, before its own ones
+fn inherit(
+    spec: &Spec,
+    blocks: &HashMap<String, BlockDef>,
+    key: &str,
+    errors: &mut MultipleErrors,
+) -> Vec<BlockField> {
+    let block_def = &blocks[key];
+    let Some(implements) = &block_def.implements else {
+        return vec![];
+    };
+    let declared = all_fields(blocks, &block_def.name.id);
+    let mut inherited: Vec<(BlockField, &str)> = vec![];
+    for interface in implements.interfaces.iter() {
+        for interface_field in all_fields(&spec.interfaces, &interface.id) {
+            let expected = interface_field.value_type.as_ref();
+            if let Some(field) = declared
+                .iter()
+                .find(|field| field.name.id == interface_field.name.id)
+            {
+                let compatible = match (&field.value_type, expected) {
+                    (Some(value_type), Some(expected)) => is_subtype(value_type, expected, spec),
+                    _ => true,
+                };
+                if !compatible {
+                    let mut err = field.span.make_error(
+                        DiagnosticCode::InvalidType,
+                        &format!(
+                            "field \"{}\" has type {}, which does not implement the type {} it has in interface {}",
+                            field.name.id,
+                            type_name(field),
+                            type_name(interface_field),
+                            interface.id
+                        ),
+                    );
+                    err.secondary(
+                        DiagnosticSpan::from(&interface_field.span)
+                            .label(&format!("declared here in {}", interface.id)),
+                    );
+                    errors.push(err);
+                }
+                continue;
+            }
+            match inherited
+                .iter()
+                .find(|(field, _)| field.name.id == interface_field.name.id)
+            {
+                Some((field, other)) if field.value_type.as_ref() != expected => {
+                    let mut err = block_def.name.span.make_error(
+                        DiagnosticCode::InvalidType,
+                        &format!(
+                            "field \"{}\" has different types in interfaces {other} and {}, so it needs to be declared in {}",
+                            field.name.id, interface.id, block_def.name.id
+                        ),
+                    );
+                    err.secondary(
+                        DiagnosticSpan::from(&field.span)
+                            .label(&format!("declared here in {other}")),
+                    );
+                    err.secondary(
+                        DiagnosticSpan::from(&interface_field.span)
+                            .label(&format!("declared here in {}", interface.id)),
+                    );
+                    errors.push(err);
+                }
+                Some(_) => {}
+                None => inherited.push((interface_field.clone(), &interface.id)),
+            }
+        }
+    }
+    inherited.into_iter().map(|(field, _)| field).collect()
+}
+
+fn prepend_fields(block_def: &mut BlockDef, fields: Vec<BlockField>) {
+    let entries = std::mem::take(&mut block_def.entries);
+    block_def.entries = fields
+        .into_iter()
+        .map(|field| BlockEntry::Field(Box::new(field)))
+        .collect();
+    block_def.entries.extend(entries);
+}
+
+/// Interfaces are completed before the definitions that implement them, so that
+/// the fields inherited by an interface are also inherited by its implementations
+fn complete_interface(
+    spec: &mut Spec,
+    name: &str,
+    visited: &mut HashSet<String>,
+    errors: &mut MultipleErrors,
+) {
+    if !visited.insert(name.to_string()) {
+        return;
+    }
+    let keys: Vec<String> = spec
+        .interfaces
+        .iter()
+        .filter(|(_, interface)| interface.name.id == name)
+        .map(|(key, _)| key.clone())
+        .collect();
+    for key in keys.iter() {
+        let implemented: Vec<String> = spec.interfaces[key]
+            .implements
+            .iter()
+            .flat_map(|implements| implements.interfaces.iter())
+            .map(|interface| interface.id.clone())
+            .collect();
+        for interface in implemented {
+            complete_interface(spec, &interface, visited, errors);
+        }
+    }
+    for key in keys.iter() {
+        let fields = inherit(spec, &spec.interfaces, key, errors);
+        prepend_fields(spec.interfaces.get_mut(key).unwrap(), fields);
+    }
+}
+
+/// Adds to every type and interface the fields of the interfaces it implements that
+/// it does not declare, with their arguments, descriptions and directives, and reports
+/// the declared ones whose type does not match the one in the interface
+pub(crate) fn inherit_interface_fields(mut spec: Spec) -> Result<Spec, Box<dyn Error>> {
+    let mut errors = MultipleErrors::new();
+    let mut visited = HashSet::new();
+    let names: Vec<String> = spec
+        .interfaces
+        .values()
+        .map(|interface| interface.name.id.clone())
+        .collect();
+    for name in names {
+        complete_interface(&mut spec, &name, &mut visited, &mut errors);
+    }
+    let keys: Vec<String> = spec.types.keys().cloned().collect();
+    for key in keys {
+        let fields = inherit(&spec, &spec.types, &key, &mut errors);
+        prepend_fields(spec.types.get_mut(&key).unwrap(), fields);
+    }
+    errors.into_result(spec)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{errors_with, field_names, test_options, transpile, transpile_with};
+    use crate::TranspileSpecOptions;
+    use graphqxl_parser::{BlockEntry, Diagnostic, DiagnosticCode, Spec};
+
+    fn options() -> TranspileSpecOptions {
+        TranspileSpecOptions {
+            inherit_interface_fields: true,
+            ..test_options()
+        }
+    }
+
+    fn fields(spec: &Spec, name: &str) -> Vec<String> {
+        field_names(
+            spec.types
+                .get(name)
+                .or_else(|| spec.interfaces.get(name))
+                .unwrap(),
+        )
+    }
+
+    fn errors(content: &str) -> Vec<Diagnostic> {
+        errors_with(content, &options())
+    }
+
+    #[test]
+    fn test_inherits_missing_fields() {
+        let spec = transpile_with(
+            r#"
+interface Node { "The id" id(format: String): ID! @deprecated createdAt: String }
+type User implements Node { createdAt: String! name: String }
+"#,
+            &options(),
+        )
+        .unwrap();
+        assert_eq!(fields(&spec, "User"), vec!["id", "createdAt", "name"]);
+        let BlockEntry::Field(id) = &spec.types["User"].entries[0] else {
+            panic!("expected a field")
+        };
+        assert_eq!(id.description, "The id");
+        assert_eq!(id.args.len(), 1);
+        assert_eq!(id.directives.len(), 1);
+    }
+
+    #[test]
+    fn test_inherits_through_interfaces() {
+        let spec = transpile_with(
+            "
+interface Node { id: ID! }
+interface Resource implements Node { url: String }
+type Image implements Resource & Node { width: Int }
+",
+            &options(),
+        )
+        .unwrap();
+        assert_eq!(fields(&spec, "Resource"), vec!["id", "url"]);
+        assert_eq!(fields(&spec, "Image"), vec!["id", "url", "width"]);
+    }
+
+    #[test]
+    fn test_accepts_covariant_types() {
+        transpile_with(
+            "
+interface Node { id: ID parent: Node children: [Node] }
+type User implements Node { id: ID! parent: User children: [User!]! }
+",
+            &options(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_conflicting_types_should_fail() {
+        let errors = errors(
+            "
+interface Node { id: ID! }
+interface Named { name: String }
+interface Other { name: Int }
+type User implements Node & Named & Other { id: String }
+",
+        );
+        assert_eq!(errors.len(), 2);
+        assert!(errors
+            .iter()
+            .all(|err| err.code == DiagnosticCode::InvalidType));
+        assert_eq!(
+            errors[0].message,
+            "field \"id\" has type String, which does not implement the type ID! it has in interface Node"
+        );
+        assert_eq!(errors[0].secondary.len(), 1);
+        assert_eq!(errors[1].secondary.len(), 2);
+    }
+
+    #[test]
+    fn test_is_opt_in() {
+        let spec =
+            transpile("interface Node { id: ID! } type User implements Node { a: Int }").unwrap();
+        assert_eq!(fields(&spec, "User"), vec!["a"]);
+    }
+}
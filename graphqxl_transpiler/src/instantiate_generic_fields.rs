@@ -0,0 +1,341 @@
+use crate::transpile_spec::{transpile_def, TranspileSpecOptions};
+use crate::utils::definition_kind;
+use graphqxl_parser::{
+    BlockDef, BlockDefType, BlockEntry, DefType, DiagnosticCode, ExpandableRef, GenericBlockDef,
+    GenericUnion, Identifier, ModifiedRef, MultipleErrors, Spec, ValueBasicType, ValueType,
+};
+use std::collections::HashMap;
+use std::error::Error;
+
+/// Maximum number of times that instances can create other instances, which stops
+/// generics that instantiate themselves with ever growing arguments
+const MAX_INSTANTIATION_DEPTH: usize = 100;
+
+fn value_types(target: &mut Spec) -> impl Iterator<Item = &mut ValueType> {
+    let fields = target
+        .types
+        .values_mut()
+        .chain(target.inputs.values_mut())
+        .chain(target.interfaces.values_mut())
+        .flat_map(|block_def| block_def.entries.iter_mut())
+        .filter_map(|entry| match entry {
+            BlockEntry::Field(field) => Some(field),
+            BlockEntry::SpreadRef(_) => None,
+        });
+    let directive_args = target
+        .directives
+        .values_mut()
+        .flat_map(|directive| directive.arguments.iter_mut());
+    fields
+        .flat_map(|field| {
+            field
+                .value_type
+                .iter_mut()
+                .chain(field.args.iter_mut().map(|arg| &mut arg.value_type))
+        })
+        .chain(directive_args.map(|arg| &mut arg.value_type))
+}
+
+/// The generics instantiated in place in `target`, in the order of the definitions
+/// that use them so that their instances are always generated in the same order
+fn generic_calls(target: &Spec) -> Vec<ExpandableRef> {
+    let mut calls: Vec<ExpandableRef> = vec![];
+    for def in target.order.iter() {
+        let key = def.key();
+        let fields = [&target.types, &target.inputs, &target.interfaces]
+            .into_iter()
+            .filter_map(|blocks| blocks.get(key))
+            .flat_map(|block_def| block_def.entries.iter())
+            .filter_map(|entry| match entry {
+                BlockEntry::Field(field) => Some(field),
+                BlockEntry::SpreadRef(_) => None,
+            });
+        let directive_args = target
+            .directives
+            .get(key)
+            .into_iter()
+            .flat_map(|directive| directive.arguments.iter());
+        let value_types = fields
+            .flat_map(|field| {
+                field
+                    .value_type
+                    .iter()
+                    .chain(field.args.iter().map(|arg| &arg.value_type))
+            })
+            .chain(directive_args.map(|arg| &arg.value_type));
+        for value_type in value_types {
+            if let ValueBasicType::Generic(call) = value_type.retrieve_basic_type() {
+                if !calls.contains(call) {
+                    calls.push(*call.clone());
+                }
+            }
+        }
+    }
+    calls
+}
+
+fn display(call: &ExpandableRef) -> String {
+    ValueBasicType::Generic(Box::new(call.clone())).to_string()
+}
+
+/// Name given to the instance of a generic used in place: the names of its arguments
+/// followed by the name of the generic, like `UserConnection` for `Connection<User!>`
+fn instance_name(call: &ExpandableRef, private_prefix: &str) -> String {
+    let mut name = String::new();
+    for arg in call
+        .generic_call
+        .iter()
+        .flat_map(|generic_call| &generic_call.args)
+    {
+        match arg.retrieve_basic_type() {
+            ValueBasicType::Generic(inner) => name.push_str(&instance_name(inner, private_prefix)),
+            basic => name.push_str(&basic.to_string()),
+        }
+    }
+    let generic = &call.identifier.id;
+    name + generic.strip_prefix(private_prefix).unwrap_or(generic)
+}
+
+fn is_call(modified_ref: &ModifiedRef, call: &ExpandableRef) -> bool {
+    matches!(modified_ref, ModifiedRef::ExpandableRef(expandable_ref) if **expandable_ref == *call)
+}
+
+/// The public alias declared for exactly the same instance, like
+/// `type UserConnection = Connection<User!>`, which includes the instances created here
+fn declared_alias(spec: &Spec, call: &ExpandableRef, private_prefix: &str) -> Option<String> {
+    spec.order.iter().find_map(|def| {
+        let name = def.key();
+        if !private_prefix.is_empty() && name.starts_with(private_prefix) {
+            return None;
+        }
+        let is_alias = match def {
+            DefType::GenericType(_) => is_call(&spec.generic_types.get(name)?.modified_ref, call),
+            DefType::GenericInput(_) => is_call(&spec.generic_inputs.get(name)?.modified_ref, call),
+            DefType::GenericInterface(_) => {
+                is_call(&spec.generic_interfaces.get(name)?.modified_ref, call)
+            }
+            DefType::GenericUnion(_) => spec.generic_unions.get(name)?.expandable_ref == *call,
+            _ => false,
+        };
+        is_alias.then(|| name.to_string())
+    })
+}
+
+fn generic_block_def(
+    kind: BlockDefType,
+    name: &Identifier,
+    call: &ExpandableRef,
+) -> GenericBlockDef {
+    GenericBlockDef {
+        span: call.span.clone(),
+        description: String::new(),
+        description_variables: None,
+        kind,
+        name: name.clone(),
+        directives: vec![],
+        modified_ref: ModifiedRef::ExpandableRef(Box::new(call.clone())),
+    }
+}
+
+/// Name of the instance of the generic `call`, declaring and transpiling it if
+/// there was none yet
+fn instance_of(
+    spec: &mut Spec,
+    target: &mut Spec,
+    transpiled_store: &mut HashMap<String, BlockDef>,
+    options: &TranspileSpecOptions,
+    call: &ExpandableRef,
+    private_prefix: &str,
+) -> Result<String, Box<dyn Error>> {
+    if let Some(alias) = declared_alias(spec, call, private_prefix) {
+        return Ok(alias);
+    }
+    let name = Identifier {
+        id: instance_name(call, private_prefix),
+        span: call.span.clone(),
+    };
+    if let Some(kind) = definition_kind(spec, &name.id) {
+        return Err(call.span.make_error(
+            DiagnosticCode::InvalidGeneric,
+            &format!(
+                "{} would be instantiated as {}, which is already {kind}, declare an alias with another name for it",
+                display(call),
+                name.id
+            ),
+        ));
+    }
+    let generic = &call.identifier.id;
+    let def = if spec.types.contains_key(generic) {
+        let instance = generic_block_def(BlockDefType::Type, &name, call);
+        spec.generic_types.insert(name.id.clone(), instance);
+        DefType::GenericType(name.clone())
+    } else if spec.inputs.contains_key(generic) {
+        let instance = generic_block_def(BlockDefType::Input, &name, call);
+        spec.generic_inputs.insert(name.id.clone(), instance);
+        DefType::GenericInput(name.clone())
+    } else if spec.interfaces.contains_key(generic) {
+        let instance = generic_block_def(BlockDefType::Interface, &name, call);
+        spec.generic_interfaces.insert(name.id.clone(), instance);
+        DefType::GenericInterface(name.clone())
+    } else if spec.unions.contains_key(generic) {
+        let instance = GenericUnion {
+            span: call.span.clone(),
+            description: String::new(),
+            name: name.clone(),
+            directives: vec![],
+            expandable_ref: call.clone(),
+        };
+        spec.generic_unions.insert(name.id.clone(), instance);
+        DefType::GenericUnion(name.clone())
+    } else {
+        return Err(match definition_kind(spec, generic) {
+            Some(kind) => call.identifier.span.make_error(
+                DiagnosticCode::InvalidGeneric,
+                &format!("{generic} is {kind}, which cannot receive generic arguments"),
+            ),
+            None => call.identifier.span.make_error(
+                DiagnosticCode::UndefinedReference,
+                &format!("{generic} is undefined"),
+            ),
+        });
+    };
+    spec.order.push(def.clone());
+    transpile_def(spec, &def, options, transpiled_store, target)?;
+    Ok(name.id)
+}
+
+/// Replaces the generics instantiated in place in the value types of the transpiled
+/// fields and arguments, like `items: Connection<User!>`, with a reference to an
+/// instance of them: the alias declared for the same arguments if there is one, or a
+/// new definition named after the arguments and the generic otherwise. The instances
+/// can use generics in place too, so this is repeated until none is left.
+pub(crate) fn instantiate_generic_fields(
+    spec: &mut Spec,
+    target: &mut Spec,
+    transpiled_store: &mut HashMap<String, BlockDef>,
+    options: &TranspileSpecOptions,
+    private_prefix: &str,
+    errors: &mut MultipleErrors,
+) {
+    for _ in 0..MAX_INSTANTIATION_DEPTH {
+        let calls = generic_calls(target);
+        if calls.is_empty() {
+            return;
+        }
+        let mut instances: Vec<(ExpandableRef, String)> = vec![];
+        for call in calls {
+            let name = match instance_of(
+                spec,
+                target,
+                transpiled_store,
+                options,
+                &call,
+                private_prefix,
+            ) {
+                Ok(name) => name,
+                Err(err) => {
+                    errors.push(err);
+                    call.identifier.id.clone()
+                }
+            };
+            instances.push((call, name));
+        }
+        // the instances created in this round might have generic calls to be resolved in the next one
+        for value_type in value_types(target) {
+            let basic_type = value_type.retrieve_basic_type_mut();
+            let ValueBasicType::Generic(call) = basic_type else {
+                continue;
+            };
+            if let Some((_, name)) = instances.iter().find(|(instance, _)| **call == *instance) {
+                let identifier = Identifier {
+                    id: name.clone(),
+                    span: call.identifier.span.clone(),
+                };
+                *basic_type = ValueBasicType::Object(identifier);
+            }
+        }
+    }
+    for call in generic_calls(target) {
+        errors.push(call.span.make_error(
+            DiagnosticCode::InvalidGeneric,
+            &format!(
+                "{} is nested in too many generic instances, a generic cannot instantiate itself with growing arguments",
+                display(&call)
+            ),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{errors, fields, transpile};
+    use graphqxl_parser::{DiagnosticCode, Identifier, ValueType};
+
+    fn messages(content: &str) -> Vec<String> {
+        errors(content)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_reuses_declared_alias() {
+        let spec = transpile(
+            "
+type Box<T> { value: T }
+type IntBox = Box<Int!>
+type Query { a: Box<Int!> b: [Box<Int!>!] }
+",
+        )
+        .unwrap();
+        let query = fields(&spec.types["Query"]);
+        assert_eq!(
+            query[0].value_type,
+            Some(ValueType::object(Identifier::from("IntBox")))
+        );
+        assert_eq!(
+            query[1].value_type,
+            Some(
+                ValueType::object(Identifier::from("IntBox"))
+                    .non_nullable()
+                    .array()
+            )
+        );
+    }
+
+    #[test]
+    fn test_instances_with_the_same_name_need_an_alias() {
+        assert_eq!(
+            messages("type Box<T> { value: T } type Query { a: Box<Int!> b: Box<Int> }"),
+            vec!["Box<Int> would be instantiated as IntBox, which is already a type, declare an alias with another name for it"]
+        );
+    }
+
+    #[test]
+    fn test_only_generics_can_be_instantiated() {
+        let found = errors("enum E { A } type Query { a: E<Int> b: Missing<Int> }");
+        let codes: Vec<&DiagnosticCode> = found.iter().map(|d| &d.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                &DiagnosticCode::InvalidGeneric,
+                &DiagnosticCode::UndefinedReference
+            ]
+        );
+        assert_eq!(
+            found[0].message,
+            "E is an enum, which cannot receive generic arguments"
+        );
+    }
+
+    #[test]
+    fn test_growing_instances_are_an_error() {
+        let found = messages(
+            "type Wrap<T> { w: T } type Nest<T> { n: Nest<Wrap<T>> } type Query { a: Nest<Int> }",
+        );
+        assert_eq!(found.len(), 1);
+        assert!(found[0].ends_with(
+            "is nested in too many generic instances, a generic cannot instantiate itself with growing arguments"
+        ));
+    }
+}
@@ -1,11 +1,23 @@
 extern crate core;
 
+mod apply_overrides;
+mod builtins;
+mod check_enum_spreads;
+mod check_types;
+mod inherit_directives;
+mod inherit_interface_fields;
+mod instantiate_generic_fields;
 mod resolve_expandable_ref;
 mod resolve_modified_ref;
+mod strip_private;
 mod transpile_block_def;
 mod transpile_description;
 mod transpile_generic_block_def;
+mod transpile_generic_union;
 mod transpile_spec;
+mod transpile_union;
+mod tree_shake;
 mod utils;
 
-pub use transpile_spec::{transpile_spec, TranspileSpecOptions};
+pub use inherit_directives::DirectiveInheritance;
+pub use transpile_spec::{transpile_block, transpile_spec, TranspileSpecOptions};
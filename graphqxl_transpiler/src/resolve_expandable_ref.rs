@@ -1,58 +1,170 @@
+use crate::apply_overrides::apply_overrides;
 use crate::resolve_modified_ref::{
     resolve_modified_ref_with_context, ModifiedRefStackContext, ResolvedRef,
 };
 use crate::transpile_description::transpile_description;
 use crate::utils::BlockDefStore;
-use graphqxl_parser::{BlockEntry, ExpandableRef, ValueBasicType};
+use graphqxl_parser::{
+    ArgumentDefaultValue, BlockEntry, DiagnosticCode, ExpandableRef, Generic, ModifiedRef,
+    MultipleErrors, ValueBasicType, ValueType,
+};
 use std::collections::HashMap;
 use std::error::Error;
 
 const VARIABLES_PREFIX: &str = "variables";
 
-pub(crate) fn resolve_expandable_ref(
+/// Maps each generic parameter of the referenced definition to the value type
+/// it is instantiated with, falling back to the parameter's default.
+pub(crate) fn resolve_generic_map(
     expandable_ref: &ExpandableRef,
-    store: &BlockDefStore,
-    stack_context: ModifiedRefStackContext,
-) -> Result<ResolvedRef, Box<dyn Error>> {
-    let referenced_block_def = match store.get(&expandable_ref.identifier.id) {
-        Some(block_def) => block_def,
-        None => {
-            return Err(expandable_ref
-                .identifier
-                .span
-                .make_error(&format!("{} is undefined", &expandable_ref.identifier.id)));
-        }
-    };
-
+    generic: &Option<Generic>,
+) -> Result<HashMap<String, ValueType>, Box<dyn Error>> {
     let empty_args = vec![];
-    let generic_args = match &referenced_block_def.generic {
-        Some(generic) => &generic.args,
-        None => &empty_args,
+    let empty_defaults = vec![];
+    let (generic_args, generic_defaults) = match generic {
+        Some(generic) => (&generic.args, &generic.defaults),
+        None => (&empty_args, &empty_defaults),
     };
 
-    let generic_referenced_block_def = referenced_block_def;
-
     let empty_call_args = vec![];
     let generic_call_args = match &expandable_ref.generic_call {
         Some(generic_call) => &generic_call.args,
         None => &empty_call_args,
     };
 
-    if generic_args.len() != generic_call_args.len() {
-        return Err(expandable_ref.span.make_error(&format!(
-            "Instantiated generic type with {} args when {} where expected",
-            generic_call_args.len(),
-            generic_args.len()
-        )));
+    if generic_call_args.len() > generic_args.len() {
+        return Err(expandable_ref.span.make_error(
+            DiagnosticCode::InvalidGeneric,
+            &format!(
+                "Instantiated generic type with {} args when {} where expected",
+                generic_call_args.len(),
+                generic_args.len()
+            ),
+        ));
+    }
+
+    let missing: Vec<&str> = generic_args
+        .iter()
+        .zip(generic_defaults.iter())
+        .skip(generic_call_args.len())
+        .filter(|(_, default)| default.is_none())
+        .map(|(arg, _)| arg.id.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(expandable_ref.span.make_error(
+            DiagnosticCode::InvalidGeneric,
+            &format!(
+                "Instantiated generic type with {} args, missing generic parameters: {}",
+                generic_call_args.len(),
+                missing.join(", ")
+            ),
+        ));
     }
 
-    let mut generic_map = HashMap::new();
+    let mut generic_map: HashMap<String, ValueType> = HashMap::new();
     for i in 0..generic_args.len() {
-        generic_map.insert(
-            &generic_args.get(i).unwrap().id,
-            generic_call_args.get(i).unwrap(),
-        );
+        let value_type = match generic_call_args.get(i) {
+            Some(value_type) => value_type.clone(),
+            // defaults can reference the parameters declared before them
+            None => {
+                let mut default = generic_defaults[i].clone().unwrap();
+                replace_generic_value_type(&mut default, &generic_map)?;
+                default
+            }
+        };
+        generic_map.insert(generic_args[i].id.clone(), value_type);
+    }
+    Ok(generic_map)
+}
+
+fn replace_generic_value_type(
+    value_type: &mut ValueType,
+    generic_map: &HashMap<String, ValueType>,
+) -> Result<(), Box<dyn Error>> {
+    match value_type.retrieve_basic_type_mut() {
+        // if the type is an object...
+        ValueBasicType::Object(object) => {
+            // ...which is stored in the generic map...
+            if let Some(generic_replacement) = generic_map.get(&object.id) {
+                // ...then replace it
+                value_type.replace_basic_type(generic_replacement.clone())?;
+            }
+        }
+        // generics instantiated in place can receive the parameters as arguments
+        ValueBasicType::Generic(expandable_ref) => {
+            for arg in expandable_ref
+                .generic_call
+                .iter_mut()
+                .flat_map(|call| &mut call.args)
+            {
+                replace_generic_value_type(arg, generic_map)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn replace_generic_default(
+    default: &mut ArgumentDefaultValue,
+    generic_map: &HashMap<String, ValueType>,
+) -> Result<(), Box<dyn Error>> {
+    if let ArgumentDefaultValue::Identifier(identifier) = default {
+        match generic_map.get(&identifier.id) {
+            Some(ValueType::Basic(ValueBasicType::Object(object), _)) => {
+                *identifier = object.clone()
+            }
+            Some(other) => {
+                return Err(other.span().make_error(
+                    DiagnosticCode::InvalidGeneric,
+                    &format!(
+                    "generic parameter {} is used as a default value, so it can only be replaced by an identifier",
+                    identifier.id
+                )));
+            }
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+fn replace_generic_modified_ref(
+    modified_ref: &mut ModifiedRef,
+    generic_map: &HashMap<String, ValueType>,
+) -> Result<(), Box<dyn Error>> {
+    match modified_ref {
+        ModifiedRef::Required(inner, _)
+        | ModifiedRef::Optional(inner, _)
+        | ModifiedRef::Omit(inner, _, _)
+        | ModifiedRef::Pick(inner, _, _) => replace_generic_modified_ref(inner, generic_map),
+        ModifiedRef::ExpandableRef(expandable_ref) => {
+            if let Some(generic_call) = &mut expandable_ref.generic_call {
+                for arg in generic_call.args.iter_mut() {
+                    replace_generic_value_type(arg, generic_map)?;
+                }
+            }
+            Ok(())
+        }
     }
+}
+
+pub(crate) fn resolve_expandable_ref(
+    expandable_ref: &ExpandableRef,
+    store: &BlockDefStore,
+    stack_context: ModifiedRefStackContext,
+) -> Result<ResolvedRef, Box<dyn Error>> {
+    let referenced_block_def = match store.get(&expandable_ref.identifier.id) {
+        Some(block_def) => block_def,
+        None => {
+            return Err(expandable_ref.identifier.span.make_error(
+                DiagnosticCode::UndefinedReference,
+                &format!("{} is undefined", &expandable_ref.identifier.id),
+            ));
+        }
+    };
+
+    let generic_map = resolve_generic_map(expandable_ref, &referenced_block_def.generic)?;
+    let generic_referenced_block_def = referenced_block_def;
 
     let mut resolved_ref = ResolvedRef::init(generic_referenced_block_def);
 
@@ -73,32 +185,87 @@ pub(crate) fn resolve_expandable_ref(
         // if it is a field...
         match new_entry {
             BlockEntry::Field(mut block_field) => {
-                transpile_description(&mut block_field, &description_replacements, true)?;
-                // ...and has a type...
+                transpile_description(&mut *block_field, &description_replacements, true)?;
+                // ...and has a type, replace it...
                 if let Some(value_type) = &mut block_field.value_type {
-                    let basic_value_type = value_type.retrieve_basic_type();
-                    // ...and that type is an object...
-                    if let ValueBasicType::Object(object) = basic_value_type {
-                        // ...which is stored in the generic map...
-                        if let Some(generic_replacement) = generic_map.get(&object.id) {
-                            // ...then replace it
-                            value_type.replace_basic_type((*generic_replacement).clone())?;
-                        }
-                    }
+                    replace_generic_value_type(value_type, &generic_map)?;
+                }
+                // ...the same for its arguments
+                for arg in block_field.args.iter_mut() {
+                    replace_generic_value_type(&mut arg.value_type, &generic_map)?;
+                    replace_generic_default(&mut arg.default, &generic_map)?;
                 }
-                new_fields.push(block_field)
+                new_fields.push((*block_field, false))
             }
-            BlockEntry::SpreadRef(modified_ref) => {
+            BlockEntry::SpreadRef(mut modified_ref) => {
+                replace_generic_modified_ref(&mut modified_ref, &generic_map)?;
                 // NOTE: Careful here, recursive brain exploding ahead
                 let resolved_ref = resolve_modified_ref_with_context(
                     &modified_ref,
                     store,
                     stack_context.plus_1(),
                 )?;
-                new_fields.extend_from_slice(&resolved_ref.fields);
+                new_fields.extend(resolved_ref.fields.into_iter().map(|field| (field, true)));
             }
         }
     }
-    resolved_ref.fields = new_fields;
-    Ok(resolved_ref)
+    let mut errors = MultipleErrors::new();
+    let new_fields = apply_overrides(
+        new_fields,
+        &referenced_block_def.kind,
+        store,
+        |inherited| *inherited,
+        &mut errors,
+    );
+    resolved_ref.fields = new_fields.into_iter().map(|(field, _)| field).collect();
+    errors.into_result(resolved_ref)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{errors, fields, transpile};
+    use graphqxl_parser::{
+        Argument, ArgumentDefaultValue, BlockField, DiagnosticCode, Identifier, ValueType,
+    };
+
+    #[test]
+    fn test_replaces_generics_in_arguments_and_nested_calls() {
+        let spec =
+            transpile("type _G<T> { a(t: T): [T] } type _H<T> { ..._G<[T!]> } type A = _H<Int>")
+                .unwrap();
+        assert_eq!(
+            fields(&spec.types["A"]),
+            vec![&BlockField::build("a")
+                .arg(Argument::build(
+                    "t",
+                    ValueType::int().non_nullable().array()
+                ))
+                .value_type(ValueType::int().non_nullable().array().array())]
+        )
+    }
+
+    #[test]
+    fn test_replaces_generics_in_default_values() {
+        let spec = transpile(
+            "enum Order { ASC DESC } type _G<O> { a(order: Order = O): Int } type A = _G<DESC>",
+        )
+        .unwrap();
+        assert_eq!(
+            fields(&spec.types["A"])[0].args[0].default,
+            ArgumentDefaultValue::Identifier(Identifier::from("DESC"))
+        )
+    }
+
+    #[test]
+    fn test_generic_default_replaced_by_non_identifier_should_fail() {
+        let errors = errors(
+            "enum Order { ASC DESC } type _G<O> { a(order: Order = O): Int } type A = _G<[Int]>",
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, DiagnosticCode::InvalidGeneric);
+        assert_eq!(
+            errors[0].message,
+            "generic parameter O is used as a default value, so it can only be replaced by an identifier"
+        );
+    }
 }
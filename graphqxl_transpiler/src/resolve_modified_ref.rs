@@ -1,7 +1,8 @@
 use crate::resolve_expandable_ref::resolve_expandable_ref;
 use crate::utils::BlockDefStore;
 use graphqxl_parser::{
-    BlockDef, BlockField, Directive, Implements, ModifiedRef, OwnedSpan, ValueType,
+    BlockDef, BlockDefType, BlockField, DiagnosticCode, Directive, Identifier, Implements,
+    ModifiedRef, OwnedSpan, ValueType,
 };
 use std::error::Error;
 use std::ops::Deref;
@@ -9,6 +10,7 @@ use std::ops::Deref;
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct ResolvedRef {
     pub span: OwnedSpan,
+    pub kind: BlockDefType,
     pub implements: Option<Implements>,
     pub description: String,
     pub fields: Vec<BlockField>,
@@ -19,6 +21,7 @@ impl ResolvedRef {
     pub(crate) fn init(value: &BlockDef) -> Self {
         Self {
             span: value.span.clone(),
+            kind: value.kind.clone(),
             implements: value.implements.clone(),
             description: value.description.clone(),
             fields: vec![],
@@ -53,6 +56,47 @@ fn non_nullable(resolved_ref: &ResolvedRef) -> ResolvedRef {
     required_block_def
 }
 
+fn check_fields_exist(
+    resolved_ref: &ResolvedRef,
+    names: &[Identifier],
+) -> Result<(), Box<dyn Error>> {
+    for name in names.iter() {
+        if !resolved_ref
+            .fields
+            .iter()
+            .any(|field| field.name.id == name.id)
+        {
+            let entry = match resolved_ref.kind {
+                BlockDefType::Enum => "value",
+                _ => "field",
+            };
+            return Err(name.span.make_error(
+                DiagnosticCode::UndefinedField,
+                &format!("{entry} \"{}\" does not exist", name.id),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn omit(resolved_ref: &ResolvedRef, names: &[Identifier]) -> Result<ResolvedRef, Box<dyn Error>> {
+    check_fields_exist(resolved_ref, names)?;
+    let mut omitted_block_def = resolved_ref.clone();
+    omitted_block_def
+        .fields
+        .retain(|field| !names.iter().any(|name| name.id == field.name.id));
+    Ok(omitted_block_def)
+}
+
+fn pick(resolved_ref: &ResolvedRef, names: &[Identifier]) -> Result<ResolvedRef, Box<dyn Error>> {
+    check_fields_exist(resolved_ref, names)?;
+    let mut picked_block_def = resolved_ref.clone();
+    picked_block_def
+        .fields
+        .retain(|field| names.iter().any(|name| name.id == field.name.id));
+    Ok(picked_block_def)
+}
+
 #[derive(Default, Clone)]
 pub(crate) struct ModifiedRefStackContext {
     stack_count: usize,
@@ -66,7 +110,7 @@ impl ModifiedRefStackContext {
     }
 }
 
-const MAX_RECURSION_DEPTH: usize = 100;
+pub(crate) const MAX_RECURSION_DEPTH: usize = 100;
 
 pub(crate) fn resolve_modified_ref_with_context(
     modified_ref: &ModifiedRef,
@@ -74,9 +118,10 @@ pub(crate) fn resolve_modified_ref_with_context(
     stack_context: ModifiedRefStackContext,
 ) -> Result<ResolvedRef, Box<dyn Error>> {
     if stack_context.stack_count > MAX_RECURSION_DEPTH {
-        return Err(modified_ref
-            .span()
-            .make_error("maximum nested spread operator surpassed"));
+        return Err(modified_ref.span().make_error(
+            DiagnosticCode::SpreadDepthExceeded,
+            "maximum nested spread operator surpassed",
+        ));
     }
     match modified_ref {
         ModifiedRef::Required(modified_ref, _) => Ok(non_nullable(
@@ -87,6 +132,14 @@ pub(crate) fn resolve_modified_ref_with_context(
             store,
             stack_context.plus_1(),
         )?)),
+        ModifiedRef::Omit(modified_ref, names, _) => omit(
+            &resolve_modified_ref_with_context(modified_ref, store, stack_context.plus_1())?,
+            names,
+        ),
+        ModifiedRef::Pick(modified_ref, names, _) => pick(
+            &resolve_modified_ref_with_context(modified_ref, store, stack_context.plus_1())?,
+            names,
+        ),
         ModifiedRef::ExpandableRef(expandable_ref) => Ok(resolve_expandable_ref(
             expandable_ref,
             store,
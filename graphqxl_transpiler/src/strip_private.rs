@@ -0,0 +1,135 @@
+use crate::utils::retain_order;
+use graphqxl_parser::{BlockDef, BlockEntry, Directive, Spec};
+use std::collections::HashSet;
+
+const PRIVATE_DIRECTIVE: &str = "private";
+
+pub(crate) fn is_private(directives: &[Directive]) -> bool {
+    directives
+        .iter()
+        .any(|directive| directive.name.id == PRIVATE_DIRECTIVE)
+}
+
+/// Removes the built-in `@private` directive, so that it does not propagate to
+/// the definitions that reuse a private one
+pub(crate) fn without_private(directives: Vec<Directive>) -> Vec<Directive> {
+    directives
+        .into_iter()
+        .filter(|directive| directive.name.id != PRIVATE_DIRECTIVE)
+        .collect()
+}
+
+fn strip_private_fields(block_def: &mut BlockDef) {
+    block_def.entries.retain(|entry| match entry {
+        BlockEntry::Field(field) => !is_private(&field.directives),
+        BlockEntry::SpreadRef(_) => true,
+    })
+}
+
+/// The names of the definitions marked with the built-in `@private` directive in
+/// any of their declarations
+pub(crate) fn private_definitions(spec: &Spec) -> HashSet<String> {
+    let mut private = HashSet::new();
+    for block_def in spec
+        .types
+        .values()
+        .chain(spec.inputs.values())
+        .chain(spec.enums.values())
+        .chain(spec.interfaces.values())
+    {
+        if is_private(&block_def.directives) {
+            private.insert(block_def.name.id.clone());
+        }
+    }
+    for union in spec.unions.values() {
+        if is_private(&union.directives) {
+            private.insert(union.name.id.clone());
+        }
+    }
+    for scalar in spec.scalars.values() {
+        if is_private(&scalar.directives) {
+            private.insert(scalar.name.id.clone());
+        }
+    }
+    private
+}
+
+/// Removes the definitions, fields and enum values marked with the built-in
+/// `@private` directive. A definition is removed together with all its extensions
+/// if any of them is marked as private.
+pub(crate) fn strip_private(mut spec: Spec) -> Spec {
+    let private = private_definitions(&spec);
+
+    for block_defs in [
+        &mut spec.types,
+        &mut spec.inputs,
+        &mut spec.enums,
+        &mut spec.interfaces,
+    ] {
+        block_defs.retain(|_, def| !private.contains(&def.name.id));
+        block_defs.values_mut().for_each(strip_private_fields);
+    }
+    spec.unions.retain(|_, def| !private.contains(&def.name.id));
+    spec.scalars
+        .retain(|_, def| !private.contains(&def.name.id));
+
+    retain_order(&mut spec);
+    spec
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{field_names, transpile};
+
+    #[test]
+    fn test_removes_private_definitions() {
+        let spec = transpile(
+            "
+type Base @private { id: ID! }
+type A { ...Base a: Int }
+input I @private { a: Int }
+enum E @private { A }
+interface Node @private { id: ID! }
+union U @private = A
+scalar S @private
+",
+        )
+        .unwrap();
+        assert_eq!(spec.types.keys().collect::<Vec<_>>(), vec!["A"]);
+        assert!(spec.inputs.is_empty());
+        assert!(spec.enums.is_empty());
+        assert!(spec.interfaces.is_empty());
+        assert!(spec.unions.is_empty());
+        assert!(spec.scalars.is_empty());
+        assert_eq!(spec.order.len(), 1);
+        assert_eq!(field_names(&spec.types["A"]), vec!["id", "a"]);
+    }
+
+    #[test]
+    fn test_removes_private_fields_and_enum_values() {
+        let spec = transpile(
+            "
+type _Base { id: ID! secret: String @private }
+type A { ..._Base a: Int @private b: Int }
+enum E { A B @private }
+",
+        )
+        .unwrap();
+        assert_eq!(field_names(&spec.types["A"]), vec!["id", "b"]);
+        assert_eq!(field_names(&spec.enums["E"]), vec!["A"]);
+    }
+
+    #[test]
+    fn test_removes_extensions_of_private_definitions() {
+        let spec = transpile("type A @private { a: Int } extend type A { b: Int }").unwrap();
+        assert!(spec.types.is_empty());
+        assert!(spec.order.is_empty());
+    }
+
+    #[test]
+    fn test_private_is_not_inherited_by_aliases() {
+        let spec = transpile("type Base @private { a: Int } type A = Base").unwrap();
+        assert_eq!(spec.types.keys().collect::<Vec<_>>(), vec!["A"]);
+        assert!(spec.types["A"].directives.is_empty());
+    }
+}
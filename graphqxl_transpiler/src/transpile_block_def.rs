@@ -1,8 +1,12 @@
-use crate::resolve_modified_ref::resolve_modified_ref;
+use crate::apply_overrides::apply_overrides;
+use crate::resolve_modified_ref::{resolve_modified_ref, MAX_RECURSION_DEPTH};
 use crate::transpile_description::transpile_description;
 use crate::utils::BlockDefStore;
-use graphqxl_parser::{BlockDef, BlockEntry, Identifier};
-use std::collections::{HashMap, HashSet};
+use graphqxl_parser::{
+    BlockDef, BlockEntry, BlockField, DiagnosticCode, DiagnosticSpan, Identifier, ModifiedRef,
+    MultipleErrors, OwnedSpan,
+};
+use std::collections::HashMap;
 use std::error::Error;
 use std::string::ToString;
 
@@ -16,13 +20,116 @@ impl<'a> IdOrBlock<'a> {
         match self {
             IdOrBlock::Id(id) => match store.get(&id.id) {
                 Some(block_def) => Ok(block_def),
-                None => Err(id.span.make_error(&format!("{} is undefined", &id.id))),
+                None => Err(id.span.make_error(
+                    DiagnosticCode::UndefinedReference,
+                    &format!("{} is undefined", &id.id),
+                )),
             },
             IdOrBlock::Block(block_def) => Ok(block_def),
         }
     }
 }
 
+/// Spreads that need to be followed, starting from the ones inside the block that
+/// `modified_ref` points to, in order to reach the definition of `field`
+pub(crate) fn nested_spreads(
+    modified_ref: &ModifiedRef,
+    field: &str,
+    store: &BlockDefStore,
+    depth: usize,
+) -> Option<Vec<OwnedSpan>> {
+    if depth > MAX_RECURSION_DEPTH {
+        return None;
+    }
+    let mut inner = modified_ref;
+    let expandable_ref = loop {
+        match inner {
+            ModifiedRef::Required(modified_ref, _)
+            | ModifiedRef::Optional(modified_ref, _)
+            | ModifiedRef::Omit(modified_ref, _, _)
+            | ModifiedRef::Pick(modified_ref, _, _) => inner = modified_ref,
+            ModifiedRef::ExpandableRef(expandable_ref) => break expandable_ref,
+        }
+    };
+    let block_def = store.get(&expandable_ref.identifier.id)?;
+    for entry in block_def.entries.iter() {
+        match entry {
+            BlockEntry::Field(block_field) if block_field.name.id == field => return Some(vec![]),
+            BlockEntry::SpreadRef(modified_ref) => {
+                if let Some(mut chain) = nested_spreads(modified_ref, field, store, depth + 1) {
+                    chain.insert(0, modified_ref.span().clone());
+                    return Some(chain);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Spreads that brought `field` into a block through `spread`, starting by `spread`
+pub(crate) fn spread_chain(
+    spread: &ModifiedRef,
+    field: &str,
+    store: &BlockDefStore,
+) -> Vec<OwnedSpan> {
+    let mut chain = vec![spread.span().clone()];
+    chain.extend(nested_spreads(spread, field, store, 0).unwrap_or_default());
+    chain
+}
+
+/// Spans that explain where a field comes from: the spreads that brought it into
+/// the block, if any, followed by its definition
+fn field_origin(
+    field: &BlockField,
+    spread: Option<&ModifiedRef>,
+    store: &BlockDefStore,
+) -> Vec<OwnedSpan> {
+    let mut origin = vec![];
+    if let Some(spread) = spread {
+        origin.extend(spread_chain(spread, &field.name.id, store));
+    }
+    origin.push(field.span.clone());
+    origin
+}
+
+fn repeated_field_error(
+    (field, spread): (&BlockField, Option<&ModifiedRef>),
+    (first, first_spread): (&BlockField, Option<&ModifiedRef>),
+    store: &BlockDefStore,
+) -> Box<dyn Error> {
+    let name = &field.name.id;
+    let mut first_origin = field_origin(first, first_spread, store);
+    let first_definition = first_origin.pop().unwrap();
+    let mut origin = field_origin(field, spread, store);
+    let definition = origin.pop().unwrap();
+
+    let mut err = match origin.first() {
+        Some(spread) => spread
+            .make_error(DiagnosticCode::RepeatedField, "repeated field")
+            .label(&format!("\"{name}\" is included again by this spread")),
+        None => definition
+            .make_error(DiagnosticCode::RepeatedField, "repeated field")
+            .label(&format!("\"{name}\" is defined again here")),
+    };
+    for (i, span) in first_origin.iter().enumerate() {
+        let label = if i == 0 {
+            format!("\"{name}\" is first included by this spread")
+        } else {
+            "through this spread".to_string()
+        };
+        err.secondary(DiagnosticSpan::from(span).label(&label));
+    }
+    err.secondary(DiagnosticSpan::from(&first_definition).label("first defined here"));
+    for span in origin.iter().skip(1) {
+        err.secondary(DiagnosticSpan::from(span).label("through this spread"));
+    }
+    if !origin.is_empty() {
+        err.secondary(DiagnosticSpan::from(&definition).label("defined again here"));
+    }
+    Box::new(err)
+}
+
 pub(crate) const BLOCK_NAME: &str = "block.name";
 pub(crate) const BLOCK_TYPE: &str = "block.type";
 pub(crate) const CUSTOM: &str = "custom";
@@ -40,20 +147,36 @@ fn transpile_block_def(
     transpiled_block_def.entries.clear();
 
     let mut entries_to_evaluate = vec![];
+    let mut errors = MultipleErrors::new();
 
     for entry in block_def.entries.iter() {
         match entry {
             BlockEntry::SpreadRef(modified_ref) => {
-                let referenced_type = resolve_modified_ref(modified_ref, store)?;
-                entries_to_evaluate.extend(referenced_type.fields);
+                match resolve_modified_ref(modified_ref, store) {
+                    Ok(referenced_type) => entries_to_evaluate.extend(
+                        referenced_type
+                            .fields
+                            .into_iter()
+                            .map(|field| (field, Some(modified_ref))),
+                    ),
+                    Err(err) => errors.push(err),
+                }
             }
             BlockEntry::Field(field) => {
-                entries_to_evaluate.push(field.clone());
+                entries_to_evaluate.push((*field.clone(), None));
             }
         }
     }
 
-    let mut seen = HashSet::new();
+    let mut entries_to_evaluate = apply_overrides(
+        entries_to_evaluate,
+        &block_def.kind,
+        store,
+        |spread| spread.is_some(),
+        &mut errors,
+    );
+
+    let mut seen: HashMap<String, (BlockField, Option<&ModifiedRef>)> = HashMap::new();
 
     let block_type = &block_def.kind;
     let mut template_string_replacements = HashMap::from([
@@ -73,17 +196,22 @@ fn transpile_block_def(
         false,
     )?;
 
-    for field in entries_to_evaluate.iter_mut() {
-        if seen.contains(&field.name.id) {
-            return Err(field.span.make_error("repeated field"));
+    for (field, spread) in entries_to_evaluate.iter_mut() {
+        if let Some((first, first_spread)) = seen.get(&field.name.id) {
+            errors.push(repeated_field_error(
+                (field, *spread),
+                (first, *first_spread),
+                store,
+            ));
+            continue;
         }
-        seen.insert(field.name.id.clone());
+        seen.insert(field.name.id.clone(), (field.clone(), *spread));
         transpile_description(field, &template_string_replacements, false)?;
         transpiled_block_def
             .entries
-            .push(BlockEntry::Field(field.clone()));
+            .push(BlockEntry::Field(Box::new(field.clone())));
     }
-    Ok(transpiled_block_def)
+    errors.into_result(transpiled_block_def)
 }
 
 pub(crate) fn transpile_block_def_by_id(
@@ -103,7 +231,7 @@ pub(crate) fn transpile_block_def_by_block(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use graphqxl_parser::{BlockDef, BlockField, ModifiedRef};
+    use graphqxl_parser::{BlockDef, BlockField, ModifiedRef, ValueType};
 
     #[test]
     fn test_transpiles_one() {
@@ -211,6 +339,67 @@ mod tests {
         assert!(err.to_string().contains("repeated field"))
     }
 
+    #[test]
+    fn test_reports_all_errors_in_the_same_type() {
+        let block_def = BlockDef::type_def("MyType")
+            .spread(ModifiedRef::build("Undefined"))
+            .field(BlockField::build("field").string())
+            .field(BlockField::build("field").string());
+        let mut types = HashMap::new();
+        types.insert(block_def.name.id.clone(), block_def);
+        let err = transpile_block_def(
+            &IdOrBlock::Id(&Identifier::from("MyType")),
+            &BlockDefStore::from(&types),
+        )
+        .unwrap_err();
+        let errors = MultipleErrors::flatten(err);
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].to_string().contains("Undefined is undefined"));
+        assert!(errors[1].to_string().contains("repeated field"));
+    }
+
+    #[test]
+    fn test_omits_and_picks_fields() {
+        let block_def = BlockDef::type_def("MyType")
+            .field(BlockField::build("id").string())
+            .field(BlockField::build("name").string())
+            .field(BlockField::build("email").string());
+        let block_def_2 = BlockDef::type_def("MyType2")
+            .spread(ModifiedRef::build("MyType").omit(&["id"]))
+            .spread(ModifiedRef::build("MyType").pick(&["id"]).required());
+        let mut types = HashMap::new();
+        types.insert(block_def.name.id.clone(), block_def);
+        types.insert(block_def_2.name.id.clone(), block_def_2);
+        let transpiled = transpile_block_def(
+            &IdOrBlock::Id(&Identifier::from("MyType2")),
+            &BlockDefStore::from(&types),
+        )
+        .unwrap();
+        assert_eq!(
+            transpiled,
+            BlockDef::type_def("MyType2")
+                .field(BlockField::build("name").string())
+                .field(BlockField::build("email").string())
+                .field(BlockField::build("id").value_type(ValueType::string().non_nullable()))
+        )
+    }
+
+    #[test]
+    fn test_omitting_unknown_field_should_fail() {
+        let block_def = BlockDef::type_def("MyType").field(BlockField::build("field").string());
+        let block_def_2 =
+            BlockDef::type_def("MyType2").spread(ModifiedRef::build("MyType").omit(&["other"]));
+        let mut types = HashMap::new();
+        types.insert(block_def.name.id.clone(), block_def);
+        types.insert(block_def_2.name.id.clone(), block_def_2);
+        let err = transpile_block_def(
+            &IdOrBlock::Id(&Identifier::from("MyType2")),
+            &BlockDefStore::from(&types),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("field \"other\" does not exist"))
+    }
+
     #[test]
     fn test_undefined_spread_should_fail() {
         let block_def = BlockDef::type_def("MyType2")
@@ -1,12 +1,9 @@
 use crate::resolve_modified_ref::ResolvedRef;
-use graphqxl_parser::{BlockDef, BlockField, OwnedSpan};
+use graphqxl_parser::{BlockDef, BlockField, DiagnosticCode, OwnedSpan};
 use regex::{escape, Regex};
 use std::collections::HashMap;
 use std::error::Error;
 
-#[derive(Debug)]
-struct Struct;
-
 pub(crate) trait TemplateDescription {
     fn get_description(&self) -> &str;
     fn mutate_description(&mut self, new_description: &str);
@@ -55,15 +52,16 @@ pub(crate) fn transpile_description<T: TemplateDescription>(
             Err(err) => {
                 return Err(with_template_description
                     .owned_span()
-                    .make_error(&err.to_string()));
+                    .make_error(DiagnosticCode::InvalidDescription, &err.to_string()));
             }
         };
         replaced = re.replace_all(&replaced, value).to_string();
     }
     if !allow_missing_replacements && any_template.is_match(&replaced) {
-        return Err(with_template_description
-            .owned_span()
-            .make_error("Not all the template variables where resolved"));
+        return Err(with_template_description.owned_span().make_error(
+            DiagnosticCode::InvalidDescription,
+            "Not all the template variables where resolved",
+        ));
     }
     with_template_description.mutate_description(&replaced);
     Ok(())
@@ -117,8 +115,9 @@ mod tests {
         match result {
             Ok(_) => panic!("should have failed"),
             Err(err) => {
-                assert!(format!("{}", err)
-                    .ends_with(":0 Not all the template variables where resolved"),)
+                assert!(format!("{}", err).starts_with(
+                    "error[invalid-description]: Not all the template variables where resolved"
+                ))
             }
         }
     }
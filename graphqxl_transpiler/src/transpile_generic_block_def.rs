@@ -1,5 +1,6 @@
 use std::error::Error;
 
+use crate::inherit_directives::DirectiveRules;
 use crate::resolve_modified_ref::resolve_modified_ref;
 use crate::utils::BlockDefStore;
 use graphqxl_parser::{BlockDef, BlockEntry, GenericBlockDef};
@@ -7,11 +8,15 @@ use graphqxl_parser::{BlockDef, BlockEntry, GenericBlockDef};
 pub(crate) fn transpile_generic_block_def(
     generic_block_def: &GenericBlockDef,
     store: &BlockDefStore,
+    rules: &DirectiveRules,
 ) -> Result<BlockDef, Box<dyn Error>> {
     let resolved = resolve_modified_ref(&generic_block_def.modified_ref, store)?;
 
-    let mut directives = resolved.directives.clone();
-    directives.extend(generic_block_def.directives.clone());
+    let referenced = match store.get(&generic_block_def.modified_ref.identifier().id) {
+        Some(referenced) => rules.of(referenced, store, 0),
+        None => resolved.directives.clone(),
+    };
+    let directives = rules.inherit_from_generic(generic_block_def.directives.clone(), referenced);
 
     let description = if !generic_block_def.description.is_empty() {
         generic_block_def.description.clone()
@@ -32,7 +37,7 @@ pub(crate) fn transpile_generic_block_def(
         entries: resolved
             .fields
             .iter()
-            .map(|el| BlockEntry::Field(el.clone()))
+            .map(|el| BlockEntry::Field(Box::new(el.clone())))
             .collect(),
     })
 }
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use crate::inherit_directives::DirectiveRules;
+use crate::resolve_expandable_ref::resolve_generic_map;
+use graphqxl_parser::{
+    DiagnosticCode, GenericUnion, Union, UnionMember, ValueBasicType, ValueType,
+};
+
+pub(crate) fn transpile_generic_union(
+    generic_union: &GenericUnion,
+    unions: &HashMap<String, Union>,
+    rules: &DirectiveRules,
+) -> Result<Union, Box<dyn Error>> {
+    let expandable_ref = &generic_union.expandable_ref;
+    let referenced_union = match unions.get(&expandable_ref.identifier.id) {
+        Some(union) => union,
+        None => {
+            return Err(expandable_ref.identifier.span.make_error(
+                DiagnosticCode::UndefinedReference,
+                &format!("{} is undefined", &expandable_ref.identifier.id),
+            ));
+        }
+    };
+
+    let generic_map = resolve_generic_map(expandable_ref, &referenced_union.generic)?;
+
+    let mut members = vec![];
+    for member in referenced_union.members.iter() {
+        let UnionMember::Type(member) = member else {
+            members.push(member.clone());
+            continue;
+        };
+        match generic_map.get(&member.id) {
+            Some(ValueType::Basic(ValueBasicType::Object(object), _)) => {
+                members.push(UnionMember::Type(object.clone()))
+            }
+            Some(other) => {
+                return Err(other.span().make_error(
+                    DiagnosticCode::InvalidGeneric,
+                    &format!(
+                        "generic parameter {} of union {} can only be replaced by an object type",
+                        member.id, referenced_union.name.id
+                    ),
+                ));
+            }
+            None => members.push(UnionMember::Type(member.clone())),
+        }
+    }
+
+    let directives = rules.inherit_from_generic(
+        generic_union.directives.clone(),
+        referenced_union.directives.clone(),
+    );
+
+    let description = if !generic_union.description.is_empty() {
+        generic_union.description.clone()
+    } else {
+        referenced_union.description.clone()
+    };
+
+    Ok(Union {
+        extend: false,
+        span: generic_union.span.clone(),
+        name: generic_union.name.clone(),
+        generic: None,
+        description,
+        members,
+        directives,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inherit_directives::DirectiveInheritance;
+    use graphqxl_parser::{Directive, Generic, Identifier};
+
+    fn unions() -> HashMap<String, Union> {
+        HashMap::from([(
+            "Result".to_string(),
+            Union::build("Result")
+                .generic(Generic::from("T"))
+                .description("result")
+                .type_("T")
+                .type_("Error"),
+        )])
+    }
+
+    fn transpile(generic_union: &GenericUnion) -> Result<Union, Box<dyn Error>> {
+        let definitions = HashMap::new();
+        let rules = DirectiveRules {
+            spreads: &DirectiveInheritance::None,
+            generics: &DirectiveInheritance::All,
+            definitions: &definitions,
+        };
+        transpile_generic_union(generic_union, &unions(), &rules)
+    }
+
+    #[test]
+    fn test_replaces_generic_members() {
+        let generic_union = GenericUnion::build("UserResult", "Result")
+            .arg(ValueType::object(Identifier::from("User")))
+            .directive(Directive::build("dir"));
+        assert_eq!(
+            transpile(&generic_union).unwrap(),
+            Union::build("UserResult")
+                .description("result")
+                .type_("User")
+                .type_("Error")
+                .directive(Directive::build("dir"))
+        )
+    }
+
+    #[test]
+    fn test_does_not_allow_non_object_members() {
+        let generic_union = GenericUnion::build("UserResult", "Result")
+            .arg(ValueType::object(Identifier::from("User")).non_nullable());
+        transpile(&generic_union).unwrap_err();
+    }
+
+    #[test]
+    fn test_does_not_allow_missing_args() {
+        let generic_union = GenericUnion::build("UserResult", "Result");
+        transpile(&generic_union).unwrap_err();
+    }
+}
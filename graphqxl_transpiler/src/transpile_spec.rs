@@ -1,112 +1,450 @@
+use crate::builtins::{remove_unused_builtins, with_builtins};
+use crate::check_enum_spreads::check_enum_spreads;
+use crate::check_types::check_types;
+use crate::inherit_directives::{DirectiveInheritance, DirectiveRules};
+use crate::inherit_interface_fields::inherit_interface_fields;
+use crate::instantiate_generic_fields::instantiate_generic_fields;
+use crate::strip_private::strip_private;
 use crate::transpile_block_def::{transpile_block_def_by_block, transpile_block_def_by_id};
 use crate::transpile_generic_block_def::transpile_generic_block_def;
+use crate::transpile_generic_union::transpile_generic_union;
+use crate::transpile_union::transpile_union;
+use crate::tree_shake::tree_shake;
 use crate::utils::BlockDefStore;
-use graphqxl_parser::{DefType, Spec};
+use graphqxl_parser::{BlockDef, DefType, DiagnosticCode, MultipleErrors, Spec};
 use std::collections::HashMap;
 use std::error::Error;
 
-#[derive(Clone, Default, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TranspileSpecOptions {
     pub private_prefix: String,
+    /// Drop everything that cannot be reached from the schema roots
+    pub tree_shake: bool,
+    /// Add to types and interfaces the fields of the interfaces they implement
+    pub inherit_interface_fields: bool,
+    /// Block level directives inherited from the definitions included with spreads
+    pub spread_directives: DirectiveInheritance,
+    /// Block level directives inherited from the definitions instantiated by generics
+    pub generic_directives: DirectiveInheritance,
 }
 
-pub fn transpile_spec(spec: &Spec, options: &TranspileSpecOptions) -> Result<Spec, Box<dyn Error>> {
-    let mut target = Spec::default();
-    let mut transpiled_store = HashMap::new();
+impl Default for TranspileSpecOptions {
+    fn default() -> Self {
+        Self {
+            private_prefix: String::new(),
+            tree_shake: false,
+            inherit_interface_fields: false,
+            spread_directives: DirectiveInheritance::None,
+            generic_directives: DirectiveInheritance::All,
+        }
+    }
+}
 
-    for def in spec.order.iter() {
-        let types_block_def_store = BlockDefStore::from(vec![
-            &spec.types,
-            &transpiled_store,
-            &spec.interfaces,
-            &spec.inputs,
-        ]);
+impl TranspileSpecOptions {
+    fn is_private(&self, name: &str) -> bool {
+        !self.private_prefix.is_empty() && name.starts_with(&self.private_prefix)
+    }
 
-        let inputs_block_def_store =
-            BlockDefStore::from(vec![&spec.inputs, &transpiled_store, &spec.types]);
+    fn directive_rules<'a>(&'a self, spec: &'a Spec) -> DirectiveRules<'a> {
+        DirectiveRules {
+            spreads: &self.spread_directives,
+            generics: &self.generic_directives,
+            definitions: &spec.directives,
+        }
+    }
+}
 
-        let enums_block_def_store = BlockDefStore::from(&spec.enums);
+fn types_store<'a>(
+    spec: &'a Spec,
+    transpiled_store: &'a HashMap<String, BlockDef>,
+) -> BlockDefStore<'a> {
+    BlockDefStore::from(vec![
+        &spec.types,
+        transpiled_store,
+        &spec.interfaces,
+        &spec.inputs,
+    ])
+    .with_spec(spec)
+}
 
-        let interfaces_block_def_store = BlockDefStore::from(&spec.interfaces);
+fn inputs_store<'a>(
+    spec: &'a Spec,
+    transpiled_store: &'a HashMap<String, BlockDef>,
+) -> BlockDefStore<'a> {
+    BlockDefStore::from(vec![&spec.inputs, transpiled_store, &spec.types]).with_spec(spec)
+}
 
-        match def {
-            DefType::Type(name) => {
-                if name.id.starts_with(&options.private_prefix) {
-                    continue;
-                }
-                let transpiled = transpile_block_def_by_id(name, &types_block_def_store)?;
-                if transpiled.generic.is_none() {
-                    target.types.insert(name.id.clone(), transpiled);
-                    target.order.push(DefType::Type(name.clone()));
+fn interfaces_store<'a>(
+    spec: &'a Spec,
+    transpiled_store: &'a HashMap<String, BlockDef>,
+) -> BlockDefStore<'a> {
+    BlockDefStore::from(vec![&spec.interfaces, transpiled_store]).with_spec(spec)
+}
+
+/// The enums that can be spread, each one with the values of its extensions too
+fn enums_with_extensions(spec: &Spec) -> HashMap<String, BlockDef> {
+    spec.enums
+        .iter()
+        .filter(|(key, _)| !Spec::is_extension_key(key))
+        .map(|(key, enum_def)| {
+            let mut merged = enum_def.clone();
+            for extension_key in spec.keys_of(key).filter(|k| Spec::is_extension_key(k)) {
+                if let Some(extension) = spec.enums.get(extension_key) {
+                    merged.entries.extend(extension.entries.iter().cloned());
                 }
             }
-            DefType::GenericType(name) => {
-                let generic_type = if let Some(generic_type) = spec.generic_types.get(&name.id) {
-                    generic_type
-                } else {
-                    return Err(name.span.make_error("generic type not found"));
-                };
-                let resolved = transpile_generic_block_def(generic_type, &types_block_def_store)?;
-                let transpiled = transpile_block_def_by_block(&resolved, &types_block_def_store)?;
-                transpiled_store.insert(name.id.clone(), transpiled.clone());
+            (key.clone(), merged)
+        })
+        .collect()
+}
+
+pub(crate) fn transpile_def(
+    spec: &Spec,
+    def: &DefType,
+    options: &TranspileSpecOptions,
+    transpiled_store: &mut HashMap<String, BlockDef>,
+    target: &mut Spec,
+) -> Result<(), Box<dyn Error>> {
+    let types_block_def_store = types_store(spec, transpiled_store);
+
+    let inputs_block_def_store = inputs_store(spec, transpiled_store);
+
+    let interfaces_block_def_store = interfaces_store(spec, transpiled_store);
+
+    let rules = options.directive_rules(spec);
+
+    match def {
+        DefType::Type(name) => {
+            if options.is_private(&name.id) {
+                return Ok(());
+            }
+            let mut transpiled = transpile_block_def_by_id(name, &types_block_def_store)?;
+            if transpiled.generic.is_none() {
+                rules.inherit_from_spreads(
+                    &mut transpiled,
+                    &spec.types[&name.id],
+                    &types_block_def_store,
+                );
                 target.types.insert(name.id.clone(), transpiled);
                 target.order.push(DefType::Type(name.clone()));
             }
-            DefType::Input(name) => {
-                if name.id.starts_with(&options.private_prefix) {
-                    continue;
-                }
-                let transpiled = transpile_block_def_by_id(name, &inputs_block_def_store)?;
-                if transpiled.generic.is_none() {
-                    target.inputs.insert(name.id.clone(), transpiled);
-                    target.order.push(DefType::Input(name.clone()));
-                }
+        }
+        DefType::GenericType(name) => {
+            let generic_type = if let Some(generic_type) = spec.generic_types.get(&name.id) {
+                generic_type
+            } else {
+                return Err(name
+                    .span
+                    .make_error(DiagnosticCode::UndefinedReference, "generic type not found"));
+            };
+            let resolved =
+                transpile_generic_block_def(generic_type, &types_block_def_store, &rules)?;
+            let transpiled = transpile_block_def_by_block(&resolved, &types_block_def_store)?;
+            transpiled_store.insert(name.id.clone(), transpiled.clone());
+            target.types.insert(name.id.clone(), transpiled);
+            target.order.push(DefType::Type(name.clone()));
+        }
+        DefType::Input(name) => {
+            if options.is_private(&name.id) {
+                return Ok(());
             }
-            DefType::GenericInput(name) => {
-                let generic_input = if let Some(generic_input) = spec.generic_inputs.get(&name.id) {
-                    generic_input
-                } else {
-                    return Err(name.span.make_error("generic input not found"));
-                };
-                let resolved = transpile_generic_block_def(generic_input, &inputs_block_def_store)?;
-                let transpiled = transpile_block_def_by_block(&resolved, &inputs_block_def_store)?;
-                transpiled_store.insert(name.id.clone(), transpiled.clone());
+            let mut transpiled = transpile_block_def_by_id(name, &inputs_block_def_store)?;
+            if transpiled.generic.is_none() {
+                rules.inherit_from_spreads(
+                    &mut transpiled,
+                    &spec.inputs[&name.id],
+                    &inputs_block_def_store,
+                );
                 target.inputs.insert(name.id.clone(), transpiled);
                 target.order.push(DefType::Input(name.clone()));
             }
-            DefType::Enum(name) => {
-                let transpiled = transpile_block_def_by_id(name, &enums_block_def_store)?;
-                target.enums.insert(name.id.clone(), transpiled);
-                target.order.push(DefType::Enum(name.clone()));
-            }
-            DefType::Interface(name) => {
-                let transpiled = transpile_block_def_by_id(name, &interfaces_block_def_store)?;
+        }
+        DefType::GenericInput(name) => {
+            let generic_input = if let Some(generic_input) = spec.generic_inputs.get(&name.id) {
+                generic_input
+            } else {
+                return Err(name.span.make_error(
+                    DiagnosticCode::UndefinedReference,
+                    "generic input not found",
+                ));
+            };
+            let resolved =
+                transpile_generic_block_def(generic_input, &inputs_block_def_store, &rules)?;
+            let transpiled = transpile_block_def_by_block(&resolved, &inputs_block_def_store)?;
+            transpiled_store.insert(name.id.clone(), transpiled.clone());
+            target.inputs.insert(name.id.clone(), transpiled);
+            target.order.push(DefType::Input(name.clone()));
+        }
+        DefType::Enum(name) => {
+            let enum_def = &spec.enums[&name.id];
+            check_enum_spreads(spec, enum_def)?;
+            let enums = enums_with_extensions(spec);
+            let enums_block_def_store = BlockDefStore::from(&enums);
+            let mut transpiled = transpile_block_def_by_block(enum_def, &enums_block_def_store)?;
+            rules.inherit_from_spreads(&mut transpiled, enum_def, &enums_block_def_store);
+            target.enums.insert(name.id.clone(), transpiled);
+            target.order.push(DefType::Enum(name.clone()));
+        }
+        DefType::Interface(name) => {
+            let mut transpiled = transpile_block_def_by_id(name, &interfaces_block_def_store)?;
+            if transpiled.generic.is_none() {
+                rules.inherit_from_spreads(
+                    &mut transpiled,
+                    &spec.interfaces[&name.id],
+                    &interfaces_block_def_store,
+                );
                 target.interfaces.insert(name.id.clone(), transpiled);
                 target.order.push(DefType::Interface(name.clone()));
             }
-            DefType::Scalar(name) => {
-                let transpiled = spec.scalars.get(&name.id).unwrap();
-                target.scalars.insert(name.id.clone(), transpiled.clone());
-                target.order.push(DefType::Scalar(name.clone()));
-            }
-            DefType::Union(name) => {
-                let transpiled = spec.unions.get(&name.id).unwrap();
-                target.unions.insert(name.id.clone(), transpiled.clone());
+        }
+        DefType::GenericInterface(name) => {
+            let generic_interface =
+                if let Some(generic_interface) = spec.generic_interfaces.get(&name.id) {
+                    generic_interface
+                } else {
+                    return Err(name.span.make_error(
+                        DiagnosticCode::UndefinedReference,
+                        "generic interface not found",
+                    ));
+                };
+            let resolved = transpile_generic_block_def(
+                generic_interface,
+                &interfaces_block_def_store,
+                &rules,
+            )?;
+            let transpiled = transpile_block_def_by_block(&resolved, &interfaces_block_def_store)?;
+            transpiled_store.insert(name.id.clone(), transpiled.clone());
+            target.interfaces.insert(name.id.clone(), transpiled);
+            target.order.push(DefType::Interface(name.clone()));
+        }
+        DefType::Scalar(name) => {
+            let transpiled = spec.scalars.get(&name.id).unwrap();
+            target.scalars.insert(name.id.clone(), transpiled.clone());
+            target.order.push(DefType::Scalar(name.clone()));
+        }
+        DefType::Union(name) => {
+            let union = spec.unions.get(&name.id).unwrap();
+            if union.generic.is_none() {
+                let transpiled = transpile_union(union, spec)?;
+                target.unions.insert(name.id.clone(), transpiled);
                 target.order.push(DefType::Union(name.clone()));
             }
-            DefType::Directive(name) => {
-                let transpiled = spec.directives.get(&name.id).unwrap();
-                target
-                    .directives
-                    .insert(name.id.clone(), transpiled.clone());
-                target.order.push(DefType::Directive(name.clone()));
-            }
-            DefType::Schema(name) => {
-                let transpiled = spec.schemas.get(name).unwrap();
-                target.schemas.insert(name.clone(), transpiled.clone());
-                target.order.push(DefType::Schema(name.clone()))
-            }
+        }
+        DefType::GenericUnion(name) => {
+            let generic_union = if let Some(generic_union) = spec.generic_unions.get(&name.id) {
+                generic_union
+            } else {
+                return Err(name.span.make_error(
+                    DiagnosticCode::UndefinedReference,
+                    "generic union not found",
+                ));
+            };
+            let resolved = transpile_generic_union(generic_union, &spec.unions, &rules)?;
+            let transpiled = transpile_union(&resolved, spec)?;
+            target.unions.insert(name.id.clone(), transpiled);
+            target.order.push(DefType::Union(name.clone()));
+        }
+        DefType::Directive(name) => {
+            let transpiled = spec.directives.get(&name.id).unwrap();
+            target
+                .directives
+                .insert(name.id.clone(), transpiled.clone());
+            target.order.push(DefType::Directive(name.clone()));
+        }
+        DefType::Schema(name) => {
+            let transpiled = spec.schemas.get(name).unwrap();
+            target.schemas.insert(name.clone(), transpiled.clone());
+            target.order.push(DefType::Schema(name.clone()))
         }
     }
+    Ok(())
+}
+
+pub fn transpile_spec(spec: &Spec, options: &TranspileSpecOptions) -> Result<Spec, Box<dyn Error>> {
+    let (mut spec, builtins) = with_builtins(spec);
+    let mut target = Spec::default();
+    let mut transpiled_store = HashMap::new();
+    let mut errors = MultipleErrors::new();
+
+    for def in spec.order.iter() {
+        // keep going so that all the errors in the spec are reported at once
+        if let Err(err) = transpile_def(&spec, def, options, &mut transpiled_store, &mut target) {
+            errors.push(err);
+        }
+    }
+    instantiate_generic_fields(
+        &mut spec,
+        &mut target,
+        &mut transpiled_store,
+        options,
+        &options.private_prefix,
+        &mut errors,
+    );
+    let spec = &spec;
+    let mut target = errors.into_result(target)?;
+    if options.inherit_interface_fields {
+        target = inherit_interface_fields(target)?;
+    }
+    let target = remove_unused_builtins(target, &builtins);
+    // checked once the private definitions are gone, as they cannot be referenced
+    let target = strip_private(target);
+    check_types(&target, spec, &options.private_prefix)?;
+    if options.tree_shake {
+        return Ok(tree_shake(target));
+    }
     Ok(target)
 }
+
+/// Transpiles a single type, input, enum or interface as it would look in the
+/// generated GraphQL, even if it is private, with the entries of its extensions
+/// merged into it. Only the definitions up to its last extension are transpiled.
+pub fn transpile_block(
+    spec: &Spec,
+    name: &str,
+    options: &TranspileSpecOptions,
+) -> Result<Option<BlockDef>, Box<dyn Error>> {
+    let (mut spec, _) = with_builtins(spec);
+    let private_prefix = &options.private_prefix;
+    // private definitions are not skipped, as they are the ones reused the most
+    let options = TranspileSpecOptions {
+        private_prefix: String::new(),
+        ..options.clone()
+    };
+    let keys: Vec<String> = spec.keys_of(name).map(String::from).collect();
+    let Some(last) = spec
+        .order
+        .iter()
+        .rposition(|def| keys.iter().any(|key| key == def.key()))
+    else {
+        return Ok(None);
+    };
+    let mut transpiled_store = HashMap::new();
+    let mut target = Spec::default();
+    let mut errors = MultipleErrors::new();
+
+    // the other definitions are only needed to resolve this one, but their errors
+    // could also make it look different from the generated GraphQL
+    for def in spec.order[..=last].iter() {
+        if let Err(err) = transpile_def(&spec, def, &options, &mut transpiled_store, &mut target) {
+            errors.push(err);
+        }
+    }
+    instantiate_generic_fields(
+        &mut spec,
+        &mut target,
+        &mut transpiled_store,
+        &options,
+        private_prefix,
+        &mut errors,
+    );
+    let mut target = errors.into_result(target)?;
+    let mut blocks = keys.iter().filter_map(|key| {
+        target
+            .types
+            .remove(key)
+            .or_else(|| target.inputs.remove(key))
+            .or_else(|| target.enums.remove(key))
+            .or_else(|| target.interfaces.remove(key))
+    });
+    let Some(mut block_def) = blocks.next() else {
+        return Ok(None);
+    };
+    for extension in blocks {
+        block_def.entries.extend(extension.entries);
+        block_def.directives.extend(extension.directives);
+        if let Some(implements) = extension.implements {
+            match &mut block_def.implements {
+                Some(existing) => existing.interfaces.extend(implements.interfaces),
+                None => block_def.implements = Some(implements),
+            }
+        }
+    }
+    Ok(Some(block_def))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::parse;
+    use graphqxl_parser::{BlockEntry, BlockField};
+
+    #[test]
+    fn test_transpiles_private_block() {
+        let spec = parse("type _Base { a: String } type _Other { ..._Base b: Int }");
+        assert_eq!(
+            transpile_block(&spec, "_Other", &TranspileSpecOptions::default()).unwrap(),
+            Some(
+                BlockDef::type_def("_Other")
+                    .field(BlockField::build("a").string())
+                    .field(BlockField::build("b").int())
+            )
+        )
+    }
+
+    #[test]
+    fn test_transpiles_generic_alias_block() {
+        let spec = parse("type G<T> { t: T } type A = G<Int> type B = A");
+        let transpiled = transpile_block(&spec, "B", &TranspileSpecOptions::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            transpiled.entries,
+            vec![BlockEntry::Field(Box::new(BlockField::build("t").int()))]
+        )
+    }
+
+    #[test]
+    fn test_transpiles_block_with_the_given_options() {
+        let spec =
+            parse("directive @key on OBJECT type _Base @key { a: String } type A { ..._Base }");
+        let options = TranspileSpecOptions {
+            spread_directives: DirectiveInheritance::All,
+            ..Default::default()
+        };
+        let transpiled = transpile_block(&spec, "A", &options).unwrap().unwrap();
+        assert_eq!(transpiled.directives.len(), 1);
+        let transpiled = transpile_block(&spec, "A", &TranspileSpecOptions::default());
+        assert!(transpiled.unwrap().unwrap().directives.is_empty());
+    }
+
+    #[test]
+    fn test_transpile_block_validates_enum_spreads() {
+        let spec = parse("type T { a: Int } enum E { ...T }");
+        transpile_block(&spec, "E", &TranspileSpecOptions::default()).unwrap_err();
+    }
+
+    #[test]
+    fn test_transpiles_block_with_its_extensions() {
+        let spec = parse(
+            "interface I { c: Int } type A { a: String } type B { b: Int } extend type A implements I { ...B c: Int }",
+        );
+        let transpiled = transpile_block(&spec, "A", &TranspileSpecOptions::default())
+            .unwrap()
+            .unwrap();
+        assert!(!transpiled.extend);
+        assert_eq!(
+            transpiled.entries,
+            vec![
+                BlockEntry::Field(Box::new(BlockField::build("a").string())),
+                BlockEntry::Field(Box::new(BlockField::build("b").int())),
+                BlockEntry::Field(Box::new(BlockField::build("c").int())),
+            ]
+        );
+        assert_eq!(transpiled.implements.unwrap().interfaces.len(), 1);
+    }
+
+    #[test]
+    fn test_transpile_block_reports_errors_of_previous_definitions() {
+        let spec = parse("type B { ...Missing } type A { a: String }");
+        transpile_block(&spec, "A", &TranspileSpecOptions::default()).unwrap_err();
+    }
+
+    #[test]
+    fn test_transpile_unknown_block_is_none() {
+        let spec = parse("scalar Date");
+        assert_eq!(
+            transpile_block(&spec, "Date", &TranspileSpecOptions::default()).unwrap(),
+            None
+        )
+    }
+}
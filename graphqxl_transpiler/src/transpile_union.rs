@@ -0,0 +1,191 @@
+use crate::utils::definition_kind;
+use graphqxl_parser::{DiagnosticCode, Identifier, MultipleErrors, Spec, Union, UnionMember};
+use std::error::Error;
+
+struct Flattening<'a> {
+    spec: &'a Spec,
+    /// Names of the unions being spread, starting with the one being transpiled
+    stack: Vec<&'a str>,
+    types: Vec<Identifier>,
+    errors: MultipleErrors,
+}
+
+impl<'a> Flattening<'a> {
+    fn flatten(&mut self, members: &'a [UnionMember], root_spread: Option<&'a Identifier>) {
+        for member in members.iter() {
+            match member {
+                UnionMember::Type(identifier) => {
+                    if !self.types.iter().any(|t| t.id == identifier.id) {
+                        self.types.push(identifier.clone());
+                    }
+                }
+                UnionMember::Spread(identifier) => {
+                    self.spread(identifier, root_spread.unwrap_or(identifier))
+                }
+            }
+        }
+    }
+
+    fn spread(&mut self, identifier: &'a Identifier, root_spread: &'a Identifier) {
+        if self.stack.contains(&identifier.id.as_str()) {
+            // cycles that do not go through the union being transpiled are
+            // reported when transpiling the unions that form them
+            if self.stack[0] == identifier.id {
+                let mut cycle = self.stack.clone();
+                cycle.push(&identifier.id);
+                self.errors.push(root_spread.span.make_error(
+                    DiagnosticCode::CyclicalSpread,
+                    &format!(
+                        "union {} includes itself through spreads: {}",
+                        identifier.id,
+                        cycle.join(" -> ")
+                    ),
+                ));
+            }
+            return;
+        }
+        // the members of the union include the ones added by its extensions
+        let unions: Vec<&'a Union> = self
+            .spec
+            .keys_of(&identifier.id)
+            .filter_map(|key| self.spec.unions.get(key))
+            .collect();
+        let Some(union) = unions.first() else {
+            let (code, message) = match definition_kind(self.spec, &identifier.id) {
+                Some(kind) => (
+                    DiagnosticCode::InvalidType,
+                    format!(
+                        "{} is {kind}, only unions can be spread into a union",
+                        identifier.id
+                    ),
+                ),
+                None => (
+                    DiagnosticCode::UndefinedReference,
+                    format!("{} is undefined", identifier.id),
+                ),
+            };
+            self.errors.push(identifier.span.make_error(code, &message));
+            return;
+        };
+        if union.generic.is_some() {
+            self.errors.push(identifier.span.make_error(
+                DiagnosticCode::InvalidGeneric,
+                &format!(
+                    "{} is generic, so it cannot be spread into a union",
+                    identifier.id
+                ),
+            ));
+            return;
+        }
+        self.stack.push(&identifier.id);
+        for union in unions {
+            self.flatten(&union.members, Some(root_spread));
+        }
+        self.stack.pop();
+    }
+}
+
+/// Replaces the spreads of other unions with their members, recursively, keeping
+/// only the first appearance of each member
+pub(crate) fn transpile_union(union: &Union, spec: &Spec) -> Result<Union, Box<dyn Error>> {
+    let mut flattening = Flattening {
+        spec,
+        stack: vec![&union.name.id],
+        types: vec![],
+        errors: MultipleErrors::new(),
+    };
+    flattening.flatten(&union.members, None);
+    let mut transpiled = union.clone();
+    transpiled.members = flattening
+        .types
+        .into_iter()
+        .map(UnionMember::Type)
+        .collect();
+    flattening.errors.into_result(transpiled)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{errors, transpile};
+    use graphqxl_parser::{DiagnosticCode, Spec};
+
+    fn members(spec: &Spec, name: &str) -> Vec<String> {
+        spec.unions[name].types().map(|t| t.id.clone()).collect()
+    }
+
+    #[test]
+    fn test_flattens_spread_unions() {
+        let spec = transpile(
+            "
+type Book { a: Int } type Movie { a: Int } type Song { a: Int } type User { a: Int }
+union VideoResult = Movie
+union MediaResult = Book | ...VideoResult | Song
+union SearchResult = User | ...MediaResult
+",
+        )
+        .unwrap();
+        assert_eq!(
+            members(&spec, "SearchResult"),
+            vec!["User", "Book", "Movie", "Song"]
+        );
+    }
+
+    #[test]
+    fn test_includes_members_of_extensions() {
+        let spec = transpile(
+            "
+type X { a: Int } type Y { a: Int }
+union A = X
+extend union A = Y
+union B = ...A
+",
+        )
+        .unwrap();
+        assert_eq!(members(&spec, "B"), vec!["X", "Y"]);
+    }
+
+    #[test]
+    fn test_deduplicates_members() {
+        let spec = transpile(
+            "
+type A { a: Int } type B { a: Int }
+union AB = A | B
+union Other = B | ...AB | A
+",
+        )
+        .unwrap();
+        assert_eq!(members(&spec, "Other"), vec!["B", "A"]);
+    }
+
+    #[test]
+    fn test_only_unions_can_be_spread() {
+        let errors = errors("type A { a: Int } union U = ...A | ...Missing");
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].code, DiagnosticCode::InvalidType);
+        assert_eq!(
+            errors[0].message,
+            "A is a type, only unions can be spread into a union"
+        );
+        assert_eq!(errors[1].code, DiagnosticCode::UndefinedReference);
+    }
+
+    #[test]
+    fn test_cycles_should_fail() {
+        let errors = errors(
+            "
+type A { a: Int }
+union X = A | ...Y
+union Y = ...Z
+union Z = ...X
+",
+        );
+        assert_eq!(errors.len(), 3);
+        assert!(errors
+            .iter()
+            .all(|err| err.code == DiagnosticCode::CyclicalSpread));
+        assert_eq!(
+            errors[0].message,
+            "union X includes itself through spreads: X -> Y -> Z -> X"
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use crate::utils::retain_order;
+use graphqxl_parser::{
+    Argument, BlockDef, BlockEntry, Directive, DirectiveDef, DirectiveLocation, Spec,
+    ValueBasicType, ValueType,
+};
+use std::collections::{HashMap, HashSet};
+
+const DEFAULT_ROOTS: [&str; 3] = ["Query", "Mutation", "Subscription"];
+
+/// Names referenced by a definition, split in the ones that refer to other
+/// definitions and the ones that refer to directives
+#[derive(Default)]
+pub(crate) struct References<'a> {
+    pub(crate) defs: Vec<&'a str>,
+    directives: Vec<&'a str>,
+}
+
+impl<'a> References<'a> {
+    fn value_type(&mut self, value_type: &'a ValueType) {
+        if let ValueBasicType::Object(identifier) = value_type.retrieve_basic_type() {
+            self.defs.push(&identifier.id);
+        }
+    }
+
+    fn directives(&mut self, directives: &'a [Directive]) {
+        for directive in directives.iter() {
+            self.directives.push(&directive.name.id);
+        }
+    }
+
+    fn arguments(&mut self, arguments: &'a [Argument]) {
+        for argument in arguments.iter() {
+            self.value_type(&argument.value_type);
+            self.directives(&argument.directives);
+        }
+    }
+
+    pub(crate) fn block_def(&mut self, block_def: &'a BlockDef) {
+        self.directives(&block_def.directives);
+        if let Some(implements) = &block_def.implements {
+            for interface in implements.interfaces.iter() {
+                self.defs.push(&interface.id);
+            }
+        }
+        for entry in block_def.entries.iter() {
+            if let BlockEntry::Field(field) = entry {
+                if let Some(value_type) = &field.value_type {
+                    self.value_type(value_type);
+                }
+                self.arguments(&field.args);
+                self.directives(&field.directives);
+            }
+        }
+    }
+
+    fn directive_def(&mut self, directive_def: &'a DirectiveDef) {
+        self.arguments(&directive_def.arguments);
+    }
+}
+
+/// Directives that can be used in operations need to be kept even if
+/// nothing in the schema uses them
+fn is_executable(directive_def: &DirectiveDef) -> bool {
+    directive_def.locations.iter().any(|location| {
+        matches!(
+            location,
+            DirectiveLocation::Query
+                | DirectiveLocation::Mutation
+                | DirectiveLocation::Subscription
+                | DirectiveLocation::Field
+                | DirectiveLocation::FragmentDefinition
+                | DirectiveLocation::FragmentSpread
+                | DirectiveLocation::InlineFragment
+                | DirectiveLocation::VariableDefinition
+        )
+    })
+}
+
+/// Removes every definition and directive that cannot be reached from the schema
+/// roots, or from `Query`, `Mutation` and `Subscription` if there is no schema. The
+/// types that implement a reachable interface are also considered reachable, as
+/// they can be returned from any field that returns the interface.
+pub(crate) fn tree_shake(mut spec: Spec) -> Spec {
+    // each definition, including its extensions, indexed by its name
+    let mut references: HashMap<&str, References> = HashMap::new();
+    let mut implementations: HashMap<&str, Vec<&str>> = HashMap::new();
+    for block_def in spec
+        .types
+        .values()
+        .chain(spec.inputs.values())
+        .chain(spec.enums.values())
+        .chain(spec.interfaces.values())
+    {
+        references
+            .entry(&block_def.name.id)
+            .or_default()
+            .block_def(block_def);
+        if let Some(implements) = &block_def.implements {
+            for interface in implements.interfaces.iter() {
+                implementations
+                    .entry(&interface.id)
+                    .or_default()
+                    .push(&block_def.name.id);
+            }
+        }
+    }
+    for union in spec.unions.values() {
+        let entry = references.entry(&union.name.id).or_default();
+        entry.directives(&union.directives);
+        for member in union.types() {
+            entry.defs.push(&member.id);
+        }
+    }
+    for scalar in spec.scalars.values() {
+        references
+            .entry(&scalar.name.id)
+            .or_default()
+            .directives(&scalar.directives);
+    }
+    let mut directive_references: HashMap<&str, References> = HashMap::new();
+    for directive_def in spec.directives.values() {
+        directive_references
+            .entry(&directive_def.name.id)
+            .or_default()
+            .directive_def(directive_def);
+    }
+
+    let mut roots = References::default();
+    for schema in spec.schemas.values() {
+        roots.directives(&schema.directives);
+        for root in [&schema.query, &schema.mutation, &schema.subscription] {
+            if !root.id.is_empty() {
+                roots.defs.push(&root.id);
+            }
+        }
+    }
+    if spec.schemas.is_empty() {
+        roots.defs.extend(DEFAULT_ROOTS);
+    }
+    for directive_def in spec.directives.values() {
+        if is_executable(directive_def) {
+            roots.directives.push(&directive_def.name.id);
+        }
+    }
+
+    let mut reachable: HashSet<String> = HashSet::new();
+    let mut reachable_directives: HashSet<String> = HashSet::new();
+    let mut pending = vec![roots];
+    while let Some(current) = pending.pop() {
+        for name in current.defs {
+            if !reachable.insert(name.to_string()) {
+                continue;
+            }
+            if let Some(next) = references.remove(name) {
+                pending.push(next);
+            }
+            if let Some(implementations) = implementations.remove(name) {
+                pending.push(References {
+                    defs: implementations,
+                    directives: vec![],
+                });
+            }
+        }
+        for name in current.directives {
+            if !reachable_directives.insert(name.to_string()) {
+                continue;
+            }
+            if let Some(next) = directive_references.remove(name) {
+                pending.push(next);
+            }
+        }
+    }
+
+    spec.types.retain(|_, def| reachable.contains(&def.name.id));
+    spec.inputs
+        .retain(|_, def| reachable.contains(&def.name.id));
+    spec.enums.retain(|_, def| reachable.contains(&def.name.id));
+    spec.interfaces
+        .retain(|_, def| reachable.contains(&def.name.id));
+    spec.unions
+        .retain(|_, def| reachable.contains(&def.name.id));
+    spec.scalars
+        .retain(|_, def| reachable.contains(&def.name.id));
+    spec.directives
+        .retain(|_, def| reachable_directives.contains(&def.name.id));
+
+    retain_order(&mut spec);
+    spec
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{test_options, transpile_with};
+    use crate::TranspileSpecOptions;
+
+    fn shaken_names(content: &str) -> Vec<String> {
+        let options = TranspileSpecOptions {
+            tree_shake: true,
+            ..test_options()
+        };
+        let spec = transpile_with(content, &options).unwrap();
+        let mut names: Vec<String> = spec
+            .types
+            .keys()
+            .chain(spec.inputs.keys())
+            .chain(spec.enums.keys())
+            .chain(spec.interfaces.keys())
+            .chain(spec.unions.keys())
+            .chain(spec.scalars.keys())
+            .chain(spec.directives.keys())
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_keeps_only_reachable_definitions() {
+        let names = shaken_names(
+            "
+type Query { user(filter: Filter): User }
+type User { id: ID! status: Status pet: Pet }
+input Filter { since: Date }
+enum Status { ACTIVE }
+scalar Date
+union Pet = Cat
+type Cat { name: String }
+type Unused { user: User }
+enum UnusedEnum { A }
+scalar UnusedScalar
+",
+        );
+        assert_eq!(
+            names,
+            vec!["Cat", "Date", "Filter", "Pet", "Query", "Status", "User"]
+        )
+    }
+
+    #[test]
+    fn test_starts_from_the_schema_roots() {
+        let names = shaken_names(
+            "
+schema { query: Root }
+type Root { a: A }
+type A { a: Int }
+type Query { b: B }
+type B { b: Int }
+",
+        );
+        assert_eq!(names, vec!["A", "Root"])
+    }
+
+    #[test]
+    fn test_keeps_implementations_of_reachable_interfaces() {
+        let names = shaken_names(
+            "
+type Query { node: Node }
+interface Node { id: ID! }
+type A implements Node { id: ID! }
+type B { id: ID! }
+",
+        );
+        assert_eq!(names, vec!["A", "Node", "Query"])
+    }
+
+    #[test]
+    fn test_drops_unused_directives() {
+        let names = shaken_names(
+            "
+directive @used(reason: Reason) on FIELD_DEFINITION
+directive @unused on FIELD_DEFINITION
+directive @client on FIELD
+enum Reason { A }
+type Query { a: Int @used }
+",
+        );
+        assert_eq!(names, vec!["Query", "Reason", "client", "used"])
+    }
+
+    #[test]
+    fn test_keeps_extensions_of_reachable_definitions() {
+        let options = TranspileSpecOptions {
+            tree_shake: true,
+            ..test_options()
+        };
+        let spec = transpile_with(
+            "type Query { a: A } type A { a: Int } extend type A { b: B } type B { b: Int } type C { c: Int } extend type C { d: Int }",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(spec.types.len(), 4);
+        assert_eq!(spec.order.len(), 4);
+        assert!(spec.types.values().all(|def| def.name.id != "C"));
+    }
+}
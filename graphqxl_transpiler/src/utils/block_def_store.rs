@@ -1,25 +1,41 @@
-use graphqxl_parser::BlockDef;
+use graphqxl_parser::{BlockDef, Spec};
 use std::collections::HashMap;
 
 pub(crate) struct BlockDefStore<'a> {
     hash_maps: Vec<&'a HashMap<String, BlockDef>>,
+    spec: Option<&'a Spec>,
 }
 
 impl<'a> From<&'a HashMap<String, BlockDef>> for BlockDefStore<'a> {
     fn from(value: &'a HashMap<String, BlockDef>) -> Self {
         Self {
             hash_maps: vec![value],
+            spec: None,
         }
     }
 }
 
 impl<'a> From<Vec<&'a HashMap<String, BlockDef>>> for BlockDefStore<'a> {
     fn from(value: Vec<&'a HashMap<String, BlockDef>>) -> Self {
-        Self { hash_maps: value }
+        Self {
+            hash_maps: value,
+            spec: None,
+        }
     }
 }
 
 impl<'a> BlockDefStore<'a> {
+    /// Sets the spec the stored definitions come from, needed to know which types
+    /// can be used in place of others
+    pub(crate) fn with_spec(mut self, spec: &'a Spec) -> Self {
+        self.spec = Some(spec);
+        self
+    }
+
+    pub(crate) fn spec(&self) -> Option<&Spec> {
+        self.spec
+    }
+
     pub(crate) fn get(&self, key: &str) -> Option<&BlockDef> {
         for hash_map in self.hash_maps.iter() {
             if let Some(result) = hash_map.get(key) {
@@ -0,0 +1,39 @@
+use graphqxl_parser::{BlockDefType, Spec};
+
+fn with_article(kind: &BlockDefType) -> &'static str {
+    match kind {
+        BlockDefType::Type => "a type",
+        BlockDefType::Input => "an input",
+        BlockDefType::Enum => "an enum",
+        BlockDefType::Interface => "an interface",
+    }
+}
+
+/// Kind of the definition called `name`, with its article, for error messages
+/// about definitions used where another kind was expected
+pub(crate) fn definition_kind(spec: &Spec, name: &str) -> Option<&'static str> {
+    if let Some(block_def) = spec
+        .types
+        .get(name)
+        .or_else(|| spec.inputs.get(name))
+        .or_else(|| spec.enums.get(name))
+        .or_else(|| spec.interfaces.get(name))
+    {
+        return Some(with_article(&block_def.kind));
+    }
+    if let Some(generic_block_def) = spec
+        .generic_types
+        .get(name)
+        .or_else(|| spec.generic_inputs.get(name))
+        .or_else(|| spec.generic_interfaces.get(name))
+    {
+        return Some(with_article(&generic_block_def.kind));
+    }
+    if spec.unions.contains_key(name) || spec.generic_unions.contains_key(name) {
+        return Some("a union");
+    }
+    if spec.scalars.contains_key(name) {
+        return Some("a scalar");
+    }
+    None
+}
@@ -0,0 +1,45 @@
+use graphqxl_parser::{Spec, ValueBasicType, ValueType};
+
+/// Whether the named type `name` is, or can be returned in place of, `expected`:
+/// either it implements the interface `expected`, or it is a member of the union
+/// `expected`. Extensions of both definitions are taken into account.
+pub(crate) fn is_named_subtype(spec: &Spec, name: &str, expected: &str) -> bool {
+    if name == expected {
+        return true;
+    }
+    let implements = spec
+        .types
+        .values()
+        .chain(spec.interfaces.values())
+        .filter(|block_def| block_def.name.id == name)
+        .filter_map(|block_def| block_def.implements.as_ref())
+        .flat_map(|implements| implements.interfaces.iter())
+        .any(|interface| interface.id == expected);
+    implements
+        || spec
+            .unions
+            .values()
+            .filter(|union| union.name.id == expected)
+            .flat_map(|union| union.types())
+            .any(|member| member.id == name)
+}
+
+/// Whether a field of type `value_type` can be used in place of one of type
+/// `expected` in `spec`, following the covariance rules of the GraphQL spec
+pub(crate) fn is_subtype(value_type: &ValueType, expected: &ValueType, spec: &Spec) -> bool {
+    match (value_type, expected) {
+        (ValueType::NonNullable(inner, _), ValueType::NonNullable(expected, _)) => {
+            is_subtype(inner, expected, spec)
+        }
+        (_, ValueType::NonNullable(_, _)) => false,
+        (ValueType::NonNullable(inner, _), expected) => is_subtype(inner, expected, spec),
+        (ValueType::Array(inner, _), ValueType::Array(expected, _)) => {
+            is_subtype(inner, expected, spec)
+        }
+        (ValueType::Basic(ValueBasicType::Object(name), _), ValueType::Basic(expected, _)) => {
+            is_named_subtype(spec, &name.id, &expected.to_string())
+        }
+        (ValueType::Basic(basic, _), ValueType::Basic(expected, _)) => basic == expected,
+        _ => false,
+    }
+}
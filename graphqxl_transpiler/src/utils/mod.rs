@@ -1,3 +1,13 @@
 mod block_def_store;
+mod definition_kind;
+mod is_subtype;
+mod retain_order;
+#[cfg(test)]
+mod test_utils;
 
-pub(crate) use block_def_store::*;
\ No newline at end of file
+pub(crate) use block_def_store::*;
+pub(crate) use definition_kind::*;
+pub(crate) use is_subtype::*;
+pub(crate) use retain_order::*;
+#[cfg(test)]
+pub(crate) use test_utils::*;
@@ -0,0 +1,23 @@
+use graphqxl_parser::{DefType, Spec};
+
+fn is_defined(spec: &Spec, def: &DefType) -> bool {
+    match def {
+        DefType::Type(name) | DefType::GenericType(name) => spec.types.contains_key(&name.id),
+        DefType::Input(name) | DefType::GenericInput(name) => spec.inputs.contains_key(&name.id),
+        DefType::Enum(name) => spec.enums.contains_key(&name.id),
+        DefType::Interface(name) | DefType::GenericInterface(name) => {
+            spec.interfaces.contains_key(&name.id)
+        }
+        DefType::Union(name) | DefType::GenericUnion(name) => spec.unions.contains_key(&name.id),
+        DefType::Scalar(name) => spec.scalars.contains_key(&name.id),
+        DefType::Directive(name) => spec.directives.contains_key(&name.id),
+        DefType::Schema(_) => true,
+    }
+}
+
+/// Drops from the spec's order the definitions that were removed from it
+pub(crate) fn retain_order(spec: &mut Spec) {
+    let mut order = std::mem::take(&mut spec.order);
+    order.retain(|def| is_defined(spec, def));
+    spec.order = order;
+}
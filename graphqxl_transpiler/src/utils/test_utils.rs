@@ -0,0 +1,65 @@
+use crate::{transpile_spec, TranspileSpecOptions};
+use graphqxl_parser::{
+    parse_spec_with_resolver, BlockDef, BlockEntry, BlockField, Diagnostic, InMemorySourceResolver,
+    MultipleErrors, Spec,
+};
+use std::error::Error;
+
+/// The options used by the tests unless they need other ones, with `_` as the
+/// private prefix
+pub(crate) fn test_options() -> TranspileSpecOptions {
+    TranspileSpecOptions {
+        private_prefix: "_".to_string(),
+        ..Default::default()
+    }
+}
+
+/// Parses `content` as the only file of a spec
+pub(crate) fn parse(content: &str) -> Spec {
+    let resolver = InMemorySourceResolver::new().source("spec.graphqxl", content);
+    parse_spec_with_resolver("spec.graphqxl", &resolver).unwrap()
+}
+
+pub(crate) fn transpile_with(
+    content: &str,
+    options: &TranspileSpecOptions,
+) -> Result<Spec, Box<dyn Error>> {
+    transpile_spec(&parse(content), options)
+}
+
+pub(crate) fn transpile(content: &str) -> Result<Spec, Box<dyn Error>> {
+    transpile_with(content, &test_options())
+}
+
+/// Every error found while transpiling `content`, none if it transpiles fine
+pub(crate) fn errors_with(content: &str, options: &TranspileSpecOptions) -> Vec<Diagnostic> {
+    match transpile_with(content, options) {
+        Ok(_) => vec![],
+        Err(err) => MultipleErrors::flatten(err)
+            .iter()
+            .map(|err| Diagnostic::from_error(err.as_ref()))
+            .collect(),
+    }
+}
+
+pub(crate) fn errors(content: &str) -> Vec<Diagnostic> {
+    errors_with(content, &test_options())
+}
+
+pub(crate) fn fields(block_def: &BlockDef) -> Vec<&BlockField> {
+    block_def
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            BlockEntry::Field(field) => Some(field.as_ref()),
+            BlockEntry::SpreadRef(_) => None,
+        })
+        .collect()
+}
+
+pub(crate) fn field_names(block_def: &BlockDef) -> Vec<String> {
+    fields(block_def)
+        .iter()
+        .map(|field| field.name.id.clone())
+        .collect()
+}
@@ -1,5 +1,5 @@
-use anyhow::{anyhow, Result};
 use apollo_compiler::{diagnostics::DiagnosticData, ApolloDiagnostic};
+use graphqxl_parser::{Diagnostic, DiagnosticCode, OwnedSpan, Severity};
 use graphqxl_synthesizer::SourceMapEntry;
 
 pub(crate) fn is_fatal_diagnostic(diagnostic: &ApolloDiagnostic) -> bool {
@@ -58,10 +58,10 @@ pub(crate) fn is_fatal_diagnostic(diagnostic: &ApolloDiagnostic) -> bool {
     }
 }
 
-pub(crate) fn reverse_diagnostic_map(
+pub(crate) fn find_source_span<'a>(
     diagnostic: &ApolloDiagnostic,
-    source_map: &[SourceMapEntry],
-) -> Result<()> {
+    source_map: &'a [SourceMapEntry],
+) -> Option<&'a OwnedSpan> {
     let source_offset = diagnostic.location.offset();
     let source_length = diagnostic.location.node_len();
     for entry in source_map.iter() {
@@ -73,10 +73,27 @@ pub(crate) fn reverse_diagnostic_map(
             || (dst_start <= src_start && src_end <= dst_end)
         // if source span is contained between generated span limits
         {
-            let err = entry.span.make_error(&diagnostic.data.to_string());
-            return Err(anyhow!("{err}"));
+            return Some(&entry.span);
         }
     }
+    None
+}
 
-    Err(anyhow!("{diagnostic}"))
+/// Diagnostic pointing to the place in the .graphqxl sources that produced the apollo
+/// diagnostic, or to the generated GraphQL if it cannot be traced back
+pub(crate) fn reverse_diagnostic_map(
+    diagnostic: &ApolloDiagnostic,
+    source_map: &[SourceMapEntry],
+) -> Diagnostic {
+    let code = DiagnosticCode::GraphqlValidation;
+    let message = diagnostic.data.to_string();
+    let mut reversed = match find_source_span(diagnostic, source_map) {
+        Some(span) => *span.make_error(code, &message),
+        None => Diagnostic::new(code, &message)
+            .note("this problem could not be traced back to the .graphqxl sources"),
+    };
+    if !is_fatal_diagnostic(diagnostic) {
+        reversed.severity = Severity::Warning;
+    }
+    reversed
 }
@@ -0,0 +1,172 @@
+use graphqxl_parser::{Diagnostic, DiagnosticCode, DiagnosticSpan, MultipleErrors};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::IsTerminal;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum MessageFormat {
+    /// Messages meant to be read by humans
+    #[default]
+    Human,
+    /// One JSON object per line for each diagnostic, meant to be read by other tools
+    Json,
+}
+
+/// All the diagnostics that made one of the compilation stages fail, kept structured
+/// so that they can be reported in any [MessageFormat]
+#[derive(Debug)]
+pub(crate) struct Diagnostics {
+    context: Option<String>,
+    pub(crate) diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub(crate) fn new(context: Option<&str>, err: Box<dyn Error>) -> Self {
+        Self {
+            context: context.map(str::to_string),
+            diagnostics: MultipleErrors::flatten(err)
+                .iter()
+                .map(|err| Diagnostic::from_error(err.as_ref()))
+                .collect(),
+        }
+    }
+}
+
+impl Diagnostics {
+    fn render(&self, color: bool) -> String {
+        let mut out = String::new();
+        if let Some(context) = &self.context {
+            out += &format!("{context}:\n\n");
+        }
+        if let [diagnostic] = self.diagnostics.as_slice() {
+            return out + &diagnostic.render(color);
+        }
+        for diagnostic in self.diagnostics.iter() {
+            out += &format!("{}\n\n", diagnostic.render(color));
+        }
+        out + &format!("Found {} errors", self.diagnostics.len())
+    }
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(false))
+    }
+}
+
+/// Human messages are colored only if they are going to be read in a terminal,
+/// and the user did not opt out with https://no-color.org
+fn use_color() -> bool {
+    std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+impl Error for Diagnostics {}
+
+fn span_json(span: &DiagnosticSpan) -> Value {
+    json!({
+        "file": span.file,
+        "line": span.line,
+        "col": span.col,
+        "end_line": span.end_line,
+        "end_col": span.end_col,
+        "start": span.start,
+        "end": span.end,
+        "label": span.label,
+    })
+}
+
+pub(crate) fn diagnostic_json(diagnostic: &Diagnostic) -> Value {
+    json!({
+        "code": diagnostic.code.as_str(),
+        "severity": diagnostic.severity.to_string(),
+        "message": diagnostic.message,
+        "primary": diagnostic.primary.as_ref().map(span_json),
+        "secondary": diagnostic.secondary.iter().map(span_json).collect::<Vec<_>>(),
+        "notes": diagnostic.notes,
+    })
+}
+
+pub(crate) fn report_warning(format: MessageFormat, diagnostic: &Diagnostic) {
+    match format {
+        MessageFormat::Human => eprintln!("{}\n", diagnostic.render(use_color())),
+        MessageFormat::Json => println!("{}", diagnostic_json(diagnostic)),
+    }
+}
+
+/// Prints a compilation error, in JSON each one of the diagnostics that caused it
+/// is printed in its own line
+pub(crate) fn report_error(format: MessageFormat, err: &anyhow::Error) {
+    match format {
+        MessageFormat::Human => match err.downcast_ref::<Diagnostics>() {
+            Some(diagnostics) => eprintln!("{}\n", diagnostics.render(use_color())),
+            None => eprintln!("{err}\n"),
+        },
+        MessageFormat::Json => match err.downcast_ref::<Diagnostics>() {
+            Some(diagnostics) => {
+                for diagnostic in diagnostics.diagnostics.iter() {
+                    println!("{}", diagnostic_json(diagnostic))
+                }
+            }
+            None => {
+                let diagnostic = Diagnostic::new(DiagnosticCode::Other, &err.to_string());
+                println!("{}", diagnostic_json(&diagnostic))
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphqxl_parser::{parse_spec_with_resolver, InMemorySourceResolver};
+
+    #[test]
+    fn test_serializes_diagnostics() {
+        let resolver = InMemorySourceResolver::new().source(
+            "main.graphqxl",
+            "import \"other\"\ntype A {\n  a: Int\n}\ntype A {\n  a: Int\n}",
+        );
+        let err = parse_spec_with_resolver("main.graphqxl", &resolver).unwrap_err();
+        let diagnostics = Diagnostics::new(Some("Could not parse GraphQXL spec"), err);
+        assert_eq!(diagnostics.diagnostics.len(), 2);
+        assert_eq!(
+            diagnostics.diagnostics[0].code,
+            DiagnosticCode::ImportNotFound
+        );
+        assert!(diagnostics.to_string().ends_with("Found 2 errors"));
+
+        let mut value = diagnostic_json(&diagnostics.diagnostics[1]);
+        value["primary"]["file"] = json!("main.graphqxl");
+        value["secondary"][0]["file"] = json!("main.graphqxl");
+        assert_eq!(
+            value,
+            json!({
+                "code": "duplicated-definition",
+                "severity": "error",
+                "message": "type \"A\" is already defined",
+                "primary": {
+                    "file": "main.graphqxl",
+                    "line": 5,
+                    "col": 1,
+                    "end_line": 7,
+                    "end_col": 2,
+                    "start": 35,
+                    "end": 54,
+                    "label": null,
+                },
+                "secondary": [{
+                    "file": "main.graphqxl",
+                    "line": 2,
+                    "col": 6,
+                    "end_line": 2,
+                    "end_col": 7,
+                    "start": 20,
+                    "end": 21,
+                    "label": "first defined here",
+                }],
+                "notes": [],
+            })
+        );
+    }
+}
@@ -0,0 +1,502 @@
+use graphqxl_parser::{
+    Argument, ArgumentDefaultValue, BlockDef, BlockDefType, BlockEntry, BlockField, DefType,
+    DirectiveDef, Identifier, OwnedSpan, Scalar, Spec, Union, ValueType,
+};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Criticality {
+    Breaking,
+    Dangerous,
+    Safe,
+}
+
+impl Display for Criticality {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", {
+            match self {
+                Criticality::Breaking => "BREAKING",
+                Criticality::Dangerous => "DANGEROUS",
+                Criticality::Safe => "SAFE",
+            }
+        })
+    }
+}
+
+/// A single difference between two schemas. The span points to the old schema
+/// for removals and to the new one for everything else
+#[derive(Debug, Clone)]
+pub(crate) struct Change {
+    pub(crate) criticality: Criticality,
+    pub(crate) message: String,
+    pub(crate) span: OwnedSpan,
+}
+
+#[derive(Debug, Clone)]
+enum Definition {
+    Block(Box<BlockDef>),
+    Scalar(Scalar),
+    Union(Union),
+}
+
+impl Definition {
+    fn kind(&self) -> String {
+        match self {
+            Definition::Block(block_def) => block_def.kind.to_string(),
+            Definition::Scalar(_) => "scalar".to_string(),
+            Definition::Union(_) => "union".to_string(),
+        }
+    }
+
+    fn span(&self) -> &OwnedSpan {
+        match self {
+            Definition::Block(block_def) => &block_def.name.span,
+            Definition::Scalar(scalar) => &scalar.name.span,
+            Definition::Union(union) => &union.name.span,
+        }
+    }
+
+    /// Merges an extension of the same definition into this one
+    fn extend(&mut self, extension: Definition) {
+        match (self, extension) {
+            (Definition::Block(block_def), Definition::Block(extension)) => {
+                block_def.entries.extend(extension.entries);
+                if let Some(extension_implements) = extension.implements {
+                    match &mut block_def.implements {
+                        Some(implements) => implements
+                            .interfaces
+                            .extend(extension_implements.interfaces),
+                        None => block_def.implements = Some(extension_implements),
+                    }
+                }
+            }
+            (Definition::Union(union), Definition::Union(extension)) => {
+                union.members.extend(extension.members);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Definitions of a transpiled spec in declaration order, with their extensions
+/// already merged and without the private ones
+struct Definitions {
+    order: Vec<String>,
+    definitions: HashMap<String, Definition>,
+    directives: Vec<DirectiveDef>,
+    roots: Vec<(String, Identifier)>,
+}
+
+impl Definitions {
+    fn from(spec: &Spec, private_prefix: &str) -> Self {
+        let mut result = Self {
+            order: vec![],
+            definitions: HashMap::new(),
+            directives: vec![],
+            roots: vec![],
+        };
+        for def in spec.order.iter() {
+            let (key, definition) = match def {
+                DefType::Type(id) => (id, Definition::Block(Box::new(spec.types[&id.id].clone()))),
+                DefType::Input(id) => {
+                    (id, Definition::Block(Box::new(spec.inputs[&id.id].clone())))
+                }
+                DefType::Enum(id) => (id, Definition::Block(Box::new(spec.enums[&id.id].clone()))),
+                DefType::Interface(id) => (
+                    id,
+                    Definition::Block(Box::new(spec.interfaces[&id.id].clone())),
+                ),
+                DefType::Scalar(id) => (id, Definition::Scalar(spec.scalars[&id.id].clone())),
+                DefType::Union(id) => (id, Definition::Union(spec.unions[&id.id].clone())),
+                DefType::Directive(id) => {
+                    result.directives.push(spec.directives[&id.id].clone());
+                    continue;
+                }
+                DefType::Schema(key) => {
+                    let schema = &spec.schemas[key];
+                    for (operation, root) in [
+                        ("query", &schema.query),
+                        ("mutation", &schema.mutation),
+                        ("subscription", &schema.subscription),
+                    ] {
+                        if !root.id.is_empty() {
+                            result.roots.retain(|(op, _)| op != operation);
+                            result.roots.push((operation.to_string(), root.clone()));
+                        }
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            let name = Spec::name_of_key(&key.id).to_string();
+            if name.starts_with(private_prefix) {
+                continue;
+            }
+            if let Definition::Block(block_def) = &definition {
+                if block_def.generic.is_some() {
+                    continue;
+                }
+            }
+            match result.definitions.get_mut(&name) {
+                Some(existing) => existing.extend(definition),
+                None => {
+                    result.order.push(name.clone());
+                    result.definitions.insert(name, definition);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Whether a value that used to be returned as `old` can be returned as `new`
+/// without breaking the clients that read it
+fn is_safe_output_change(old: &ValueType, new: &ValueType) -> bool {
+    match (old, new) {
+        (ValueType::NonNullable(old, _), ValueType::NonNullable(new, _)) => {
+            is_safe_output_change(old, new)
+        }
+        (_, ValueType::NonNullable(new, _)) => is_safe_output_change(old, new),
+        (ValueType::NonNullable(..), _) => false,
+        (ValueType::Array(old, _), ValueType::Array(new, _)) => is_safe_output_change(old, new),
+        (ValueType::Basic(old, _), ValueType::Basic(new, _)) => old == new,
+        _ => false,
+    }
+}
+
+/// Whether a value that used to be accepted as `old` can be accepted as `new`
+/// without breaking the clients that send it
+fn is_safe_input_change(old: &ValueType, new: &ValueType) -> bool {
+    match (old, new) {
+        (ValueType::NonNullable(old, _), ValueType::NonNullable(new, _)) => {
+            is_safe_input_change(old, new)
+        }
+        (ValueType::NonNullable(old, _), _) => is_safe_input_change(old, new),
+        (_, ValueType::NonNullable(..)) => false,
+        (ValueType::Array(old, _), ValueType::Array(new, _)) => is_safe_input_change(old, new),
+        (ValueType::Basic(old, _), ValueType::Basic(new, _)) => old == new,
+        _ => false,
+    }
+}
+
+fn is_required(value_type: &ValueType) -> bool {
+    matches!(value_type, ValueType::NonNullable(..))
+}
+
+fn fields(block_def: &BlockDef) -> Vec<&BlockField> {
+    block_def
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            BlockEntry::Field(field) => Some(field.as_ref()),
+            BlockEntry::SpreadRef(_) => None,
+        })
+        .collect()
+}
+
+fn implemented(block_def: &BlockDef) -> Vec<&Identifier> {
+    match &block_def.implements {
+        Some(implements) => implements.interfaces.iter().collect(),
+        None => vec![],
+    }
+}
+
+#[derive(Default)]
+struct Changes(Vec<Change>);
+
+impl Changes {
+    fn push(&mut self, criticality: Criticality, span: &OwnedSpan, message: String) {
+        self.0.push(Change {
+            criticality,
+            message,
+            span: span.clone(),
+        })
+    }
+
+    fn args(&mut self, old: &[Argument], new: &[Argument], owner: &str) {
+        for old_arg in old.iter() {
+            match new.iter().find(|arg| arg.name.id == old_arg.name.id) {
+                None => self.push(
+                    Criticality::Breaking,
+                    &old_arg.name.span,
+                    format!("Argument '{}' was removed from {owner}", old_arg.name.id),
+                ),
+                Some(new_arg) => {
+                    if old_arg.value_type != new_arg.value_type {
+                        let criticality =
+                            if is_safe_input_change(&old_arg.value_type, &new_arg.value_type) {
+                                Criticality::Safe
+                            } else {
+                                Criticality::Breaking
+                            };
+                        self.push(
+                            criticality,
+                            &new_arg.name.span,
+                            format!(
+                                "Type of argument '{}' on {owner} changed from '{}' to '{}'",
+                                new_arg.name.id, old_arg.value_type, new_arg.value_type
+                            ),
+                        );
+                    }
+                    if old_arg.default != new_arg.default {
+                        self.push(
+                            Criticality::Dangerous,
+                            &new_arg.name.span,
+                            format!(
+                                "Default value of argument '{}' on {owner} changed",
+                                new_arg.name.id
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+        for new_arg in new.iter() {
+            if old.iter().any(|arg| arg.name.id == new_arg.name.id) {
+                continue;
+            }
+            let criticality = match new_arg.default {
+                ArgumentDefaultValue::None if is_required(&new_arg.value_type) => {
+                    Criticality::Breaking
+                }
+                _ => Criticality::Dangerous,
+            };
+            self.push(
+                criticality,
+                &new_arg.name.span,
+                format!(
+                    "Argument '{}: {}' was added to {owner}",
+                    new_arg.name.id, new_arg.value_type
+                ),
+            );
+        }
+    }
+
+    fn block_defs(&mut self, old: &BlockDef, new: &BlockDef) {
+        let name = &new.name.id;
+        let old_fields = fields(old);
+        let new_fields = fields(new);
+        let field_label = match new.kind {
+            BlockDefType::Enum => "Enum value",
+            BlockDefType::Input => "Input field",
+            _ => "Field",
+        };
+        for old_field in old_fields.iter() {
+            let new_field = match new_fields.iter().find(|f| f.name.id == old_field.name.id) {
+                Some(new_field) => new_field,
+                None => {
+                    self.push(
+                        Criticality::Breaking,
+                        &old_field.name.span,
+                        format!("{field_label} '{name}.{}' was removed", old_field.name.id),
+                    );
+                    continue;
+                }
+            };
+            if let (Some(old_type), Some(new_type)) = (&old_field.value_type, &new_field.value_type)
+            {
+                if old_type != new_type {
+                    let is_safe = match new.kind {
+                        BlockDefType::Input => is_safe_input_change(old_type, new_type),
+                        _ => is_safe_output_change(old_type, new_type),
+                    };
+                    self.push(
+                        if is_safe {
+                            Criticality::Safe
+                        } else {
+                            Criticality::Breaking
+                        },
+                        &new_field.name.span,
+                        format!(
+                            "{field_label} '{name}.{}' changed type from '{old_type}' to '{new_type}'",
+                            new_field.name.id
+                        ),
+                    );
+                }
+            }
+            self.args(
+                &old_field.args,
+                &new_field.args,
+                &format!("field '{name}.{}'", new_field.name.id),
+            );
+        }
+        for new_field in new_fields.iter() {
+            if old_fields.iter().any(|f| f.name.id == new_field.name.id) {
+                continue;
+            }
+            let criticality = match (&new.kind, &new_field.value_type) {
+                (BlockDefType::Input, Some(value_type)) if is_required(value_type) => {
+                    Criticality::Breaking
+                }
+                (BlockDefType::Input | BlockDefType::Enum, _) => Criticality::Dangerous,
+                _ => Criticality::Safe,
+            };
+            self.push(
+                criticality,
+                &new_field.name.span,
+                format!("{field_label} '{name}.{}' was added", new_field.name.id),
+            );
+        }
+        let old_implemented = implemented(old);
+        let new_implemented = implemented(new);
+        for interface in old_implemented.iter() {
+            if !new_implemented.iter().any(|i| i.id == interface.id) {
+                self.push(
+                    Criticality::Breaking,
+                    &new.name.span,
+                    format!("'{name}' no longer implements interface '{}'", interface.id),
+                );
+            }
+        }
+        for interface in new_implemented.iter() {
+            if !old_implemented.iter().any(|i| i.id == interface.id) {
+                self.push(
+                    Criticality::Dangerous,
+                    &interface.span,
+                    format!("'{name}' now implements interface '{}'", interface.id),
+                );
+            }
+        }
+    }
+
+    fn unions(&mut self, old: &Union, new: &Union) {
+        let name = &new.name.id;
+        for member in old.types() {
+            if !new.types().any(|t| t.id == member.id) {
+                self.push(
+                    Criticality::Breaking,
+                    &member.span,
+                    format!("Member '{}' was removed from union '{name}'", member.id),
+                );
+            }
+        }
+        for member in new.types() {
+            if !old.types().any(|t| t.id == member.id) {
+                self.push(
+                    Criticality::Dangerous,
+                    &member.span,
+                    format!("Member '{}' was added to union '{name}'", member.id),
+                );
+            }
+        }
+    }
+
+    fn directive_defs(&mut self, old: &DirectiveDef, new: &DirectiveDef) {
+        let name = &new.name.id;
+        for location in old.locations.iter() {
+            if !new.locations.contains(location) {
+                self.push(
+                    Criticality::Breaking,
+                    &new.name.span,
+                    format!("Location '{location:?}' was removed from directive '@{name}'"),
+                );
+            }
+        }
+        for location in new.locations.iter() {
+            if !old.locations.contains(location) {
+                self.push(
+                    Criticality::Safe,
+                    &new.name.span,
+                    format!("Location '{location:?}' was added to directive '@{name}'"),
+                );
+            }
+        }
+        self.args(
+            &old.arguments,
+            &new.arguments,
+            &format!("directive '@{name}'"),
+        );
+    }
+}
+
+/// Compares two transpiled specs and classifies every change as breaking,
+/// dangerous or safe for the clients of the old one
+pub(crate) fn diff_specs(old: &Spec, new: &Spec, private_prefix: &str) -> Vec<Change> {
+    let old = Definitions::from(old, private_prefix);
+    let new = Definitions::from(new, private_prefix);
+    let mut changes = Changes::default();
+
+    for name in old.order.iter() {
+        let old_def = &old.definitions[name];
+        let new_def = match new.definitions.get(name) {
+            Some(new_def) => new_def,
+            None => {
+                changes.push(
+                    Criticality::Breaking,
+                    old_def.span(),
+                    format!("Type '{name}' was removed"),
+                );
+                continue;
+            }
+        };
+        match (old_def, new_def) {
+            (Definition::Block(old), Definition::Block(new)) if old.kind == new.kind => {
+                changes.block_defs(old, new)
+            }
+            (Definition::Union(old), Definition::Union(new)) => changes.unions(old, new),
+            (Definition::Scalar(_), Definition::Scalar(_)) => {}
+            _ => changes.push(
+                Criticality::Breaking,
+                new_def.span(),
+                format!(
+                    "'{name}' changed kind from '{}' to '{}'",
+                    old_def.kind(),
+                    new_def.kind()
+                ),
+            ),
+        }
+    }
+    for name in new.order.iter() {
+        if !old.definitions.contains_key(name) {
+            changes.push(
+                Criticality::Safe,
+                new.definitions[name].span(),
+                format!("Type '{name}' was added"),
+            );
+        }
+    }
+
+    for old_directive in old.directives.iter() {
+        let name = &old_directive.name.id;
+        match new.directives.iter().find(|d| &d.name.id == name) {
+            Some(new_directive) => changes.directive_defs(old_directive, new_directive),
+            None => changes.push(
+                Criticality::Breaking,
+                &old_directive.name.span,
+                format!("Directive '@{name}' was removed"),
+            ),
+        }
+    }
+    for new_directive in new.directives.iter() {
+        let name = &new_directive.name.id;
+        if !old.directives.iter().any(|d| &d.name.id == name) {
+            changes.push(
+                Criticality::Safe,
+                &new_directive.name.span,
+                format!("Directive '@{name}' was added"),
+            );
+        }
+    }
+
+    for (operation, old_root) in old.roots.iter() {
+        match new.roots.iter().find(|(op, _)| op == operation) {
+            Some((_, new_root)) if new_root.id == old_root.id => {}
+            Some((_, new_root)) => changes.push(
+                Criticality::Breaking,
+                &new_root.span,
+                format!(
+                    "Schema {operation} root changed from '{}' to '{}'",
+                    old_root.id, new_root.id
+                ),
+            ),
+            None => changes.push(
+                Criticality::Breaking,
+                &old_root.span,
+                format!("Schema {operation} root '{}' was removed", old_root.id),
+            ),
+        }
+    }
+
+    changes.0
+}
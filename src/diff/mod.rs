@@ -0,0 +1,189 @@
+mod changes;
+
+use crate::ok_or_anyhow_err::ok_or_anyhow_err;
+use anyhow::{anyhow, Result};
+use changes::{diff_specs, Change, Criticality};
+use graphqxl_parser::{parse_spec_with_resolver, SourceResolver, Spec};
+use graphqxl_transpiler::{transpile_spec, TranspileSpecOptions};
+
+#[derive(clap::Args, Debug)]
+pub(crate) struct DiffArgs {
+    #[arg(help = "Path to the old version of the .graphqxl file")]
+    old: String,
+
+    #[arg(help = "Path to the new version of the .graphqxl file")]
+    new: String,
+
+    #[arg(
+        long,
+        default_value_t = String::from("_"),
+        help = "String that needs to be prefixed to a type or an input in order to consider it private"
+    )]
+    private_prefix: String,
+}
+
+fn compile(input: &str, private_prefix: &str, resolver: &dyn SourceResolver) -> Result<Spec> {
+    let spec_result = parse_spec_with_resolver(input, resolver);
+    let spec = ok_or_anyhow_err(spec_result, "Could not parse GraphQXL spec")?;
+
+    let transpile_result = transpile_spec(
+        &spec,
+        &TranspileSpecOptions {
+            private_prefix: private_prefix.to_string(),
+            ..Default::default()
+        },
+    );
+    ok_or_anyhow_err(transpile_result, "Could not transpile graphqxl spec")
+}
+
+pub(crate) fn diff(args: &DiffArgs, resolver: &dyn SourceResolver) -> Result<Vec<Change>> {
+    let old = compile(&args.old, &args.private_prefix, resolver)?;
+    let new = compile(&args.new, &args.private_prefix, resolver)?;
+    let mut changes = diff_specs(&old, &new, &args.private_prefix);
+    changes.sort_by_key(|change| change.criticality);
+    Ok(changes)
+}
+
+fn format_change(change: &Change) -> String {
+    format!(
+        "{:<9}  {}:{}:{}  {}",
+        change.criticality.to_string(),
+        change.span.file,
+        change.span.line,
+        change.span.col,
+        change.message
+    )
+}
+
+/// Prints the changes between the two schemas and fails if any of them is breaking,
+/// so that it can be used for gating schema changes in CI
+pub(crate) fn run_diff(args: &DiffArgs, resolver: &dyn SourceResolver) -> Result<()> {
+    let changes = diff(args, resolver)?;
+    for change in changes.iter() {
+        println!("{}", format_change(change));
+    }
+    let count = |criticality: Criticality| {
+        changes
+            .iter()
+            .filter(|change| change.criticality == criticality)
+            .count()
+    };
+    let breaking = count(Criticality::Breaking);
+    println!(
+        "{} breaking, {} dangerous and {} safe changes",
+        breaking,
+        count(Criticality::Dangerous),
+        count(Criticality::Safe)
+    );
+    if breaking > 0 {
+        Err(anyhow!("Found {breaking} breaking changes"))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphqxl_parser::InMemorySourceResolver;
+
+    fn diff_sources(old: &str, new: &str) -> Vec<String> {
+        let resolver = InMemorySourceResolver::new()
+            .source("old.graphqxl", old)
+            .source("new.graphqxl", new);
+        let args = DiffArgs {
+            old: "old.graphqxl".to_string(),
+            new: "new.graphqxl".to_string(),
+            private_prefix: "_".to_string(),
+        };
+        diff(&args, &resolver)
+            .unwrap()
+            .iter()
+            .map(|change| format!("{} {}", change.criticality, change.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_detects_removed_and_added_fields() {
+        assert_eq!(
+            diff_sources(
+                "type User { id: ID! name: String }",
+                "type User { id: ID! email: String }"
+            ),
+            vec![
+                "BREAKING Field 'User.name' was removed",
+                "SAFE Field 'User.email' was added",
+            ]
+        )
+    }
+
+    #[test]
+    fn test_classifies_nullability_changes() {
+        assert_eq!(
+            diff_sources(
+                "type Query { users(limit: Int, offset: Int!): [String] user: String! }",
+                "type Query { users(limit: Int!, offset: Int): [String!] user: String }"
+            ),
+            vec![
+                "BREAKING Type of argument 'limit' on field 'Query.users' changed from 'Int' to 'Int!'",
+                "BREAKING Field 'Query.user' changed type from 'String!' to 'String'",
+                "SAFE Field 'Query.users' changed type from '[String]' to '[String!]'",
+                "SAFE Type of argument 'offset' on field 'Query.users' changed from 'Int!' to 'Int'",
+            ]
+        )
+    }
+
+    #[test]
+    fn test_detects_enum_and_union_changes() {
+        assert_eq!(
+            diff_sources(
+                "type A { a: Int } type B { b: Int } union U = A | B enum E { X Y }",
+                "type A { a: Int } type B { b: Int } union U = A extend union U = B enum E { X Z }"
+            ),
+            vec![
+                "BREAKING Enum value 'E.Y' was removed",
+                "DANGEROUS Enum value 'E.Z' was added",
+            ]
+        )
+    }
+
+    #[test]
+    fn test_detects_removed_union_members_and_types() {
+        assert_eq!(
+            diff_sources(
+                "type A { a: Int } type B { b: Int } union U = A | B",
+                "type A { a: Int } union U = A"
+            ),
+            vec![
+                "BREAKING Type 'B' was removed",
+                "BREAKING Member 'B' was removed from union 'U'",
+            ]
+        )
+    }
+
+    #[test]
+    fn test_ignores_private_definitions() {
+        assert_eq!(
+            diff_sources(
+                "type _Base { a: Int } type A { ..._Base }",
+                "type _Other { a: Int } type A { ..._Other }"
+            ),
+            Vec::<String>::new()
+        )
+    }
+
+    #[test]
+    fn test_points_removals_to_the_old_file() {
+        let resolver = InMemorySourceResolver::new()
+            .source("old.graphqxl", "type A {\n  a: Int\n  b: Int\n}")
+            .source("new.graphqxl", "type A {\n  a: Int\n}");
+        let args = DiffArgs {
+            old: "old.graphqxl".to_string(),
+            new: "new.graphqxl".to_string(),
+            private_prefix: "_".to_string(),
+        };
+        let changes = diff(&args, &resolver).unwrap();
+        assert!(format_change(&changes[0]).contains("old.graphqxl:3:3"));
+        run_diff(&args, &resolver).unwrap_err();
+    }
+}
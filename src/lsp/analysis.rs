@@ -0,0 +1,319 @@
+use crate::apollo_diagnostic_source::reverse_diagnostic_map;
+use crate::lsp::overlay_source_resolver::OverlaySourceResolver;
+use crate::source_map::utf16_col;
+use apollo_compiler::ApolloCompiler;
+use graphqxl_parser::{
+    parse_spec_with_resolver, Diagnostic as GraphqxlDiagnostic, DiagnosticSpan, MultipleErrors,
+    Severity, Spec,
+};
+use graphqxl_synthesizer::{synth_spec, SynthConfig};
+use graphqxl_transpiler::{transpile_spec, DirectiveInheritance, TranspileSpecOptions};
+use lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString,
+    Position, Range, Url,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+pub(crate) struct Analysis {
+    pub(crate) spec: Option<Spec>,
+    /// Diagnostics for every file in the import graph, files without problems have an empty list
+    pub(crate) diagnostics: HashMap<PathBuf, Vec<Diagnostic>>,
+}
+
+/// LSP positions count characters in UTF-16 code units, while the spans count them as chars
+fn span_to_lsp_range(span: &DiagnosticSpan) -> Range {
+    Range::new(
+        Position::new(
+            span.line.saturating_sub(1) as u32,
+            utf16_col(span.source_line(), span.col) as u32,
+        ),
+        Position::new(
+            span.end_line.saturating_sub(1) as u32,
+            utf16_col(span.end_source_line(), span.end_col) as u32,
+        ),
+    )
+}
+
+fn to_lsp_diagnostic(diagnostic: &GraphqxlDiagnostic, entry: &Path) -> (PathBuf, Diagnostic) {
+    let (file, range) = match &diagnostic.primary {
+        Some(span) if !span.file.is_empty() => (PathBuf::from(&span.file), span_to_lsp_range(span)),
+        Some(span) => (entry.to_path_buf(), span_to_lsp_range(span)),
+        None => (entry.to_path_buf(), Range::default()),
+    };
+    let severity = match diagnostic.severity {
+        Severity::Error => DiagnosticSeverity::ERROR,
+        Severity::Warning => DiagnosticSeverity::WARNING,
+    };
+    // secondary spans, like the first definition of a duplicated one, are shown
+    // by editors next to the diagnostic
+    let related_information: Vec<DiagnosticRelatedInformation> = diagnostic
+        .secondary
+        .iter()
+        .filter_map(|span| {
+            Some(DiagnosticRelatedInformation {
+                location: Location::new(
+                    Url::from_file_path(&span.file).ok()?,
+                    span_to_lsp_range(span),
+                ),
+                message: span.label.clone().unwrap_or_default(),
+            })
+        })
+        .collect();
+    let lsp_diagnostic = Diagnostic {
+        range,
+        severity: Some(severity),
+        related_information: (!related_information.is_empty()).then_some(related_information),
+        code: Some(NumberOrString::String(diagnostic.code.to_string())),
+        source: Some("graphqxl".to_string()),
+        message: diagnostic.message.clone(),
+        ..Default::default()
+    };
+    (file, lsp_diagnostic)
+}
+
+fn errors_to_diagnostics(err: Box<dyn Error>, entry: &Path) -> Vec<(PathBuf, Diagnostic)> {
+    MultipleErrors::flatten(err)
+        .iter()
+        .map(|err| to_lsp_diagnostic(&GraphqxlDiagnostic::from_error(err.as_ref()), entry))
+        .collect()
+}
+
+/// The options used to transpile the specs opened in the editor, read from the
+/// `initializationOptions` sent by the client with the same names as the CLI flags,
+/// falling back to the CLI defaults:
+/// `{ "privatePrefix": "_", "inheritInterfaceFields": false, "spreadDirectives": "none", "genericDirectives": "all" }`
+pub(crate) fn transpile_options(initialization_options: Option<&Value>) -> TranspileSpecOptions {
+    let option = |name: &str| initialization_options.and_then(|options| options.get(name));
+    let directives = |name: &str, default: DirectiveInheritance| {
+        option(name)
+            .and_then(Value::as_str)
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(default)
+    };
+    TranspileSpecOptions {
+        private_prefix: option("privatePrefix")
+            .and_then(Value::as_str)
+            .unwrap_or("_")
+            .to_string(),
+        inherit_interface_fields: option("inheritInterfaceFields")
+            .and_then(Value::as_bool)
+            .unwrap_or_default(),
+        spread_directives: directives("spreadDirectives", DirectiveInheritance::None),
+        generic_directives: directives("genericDirectives", DirectiveInheritance::All),
+        ..Default::default()
+    }
+}
+
+fn validate(
+    spec: &Spec,
+    entry: &Path,
+    options: &TranspileSpecOptions,
+) -> Vec<(PathBuf, Diagnostic)> {
+    let transpiled = match transpile_spec(spec, options) {
+        Ok(transpiled) => transpiled,
+        Err(err) => return errors_to_diagnostics(err, entry),
+    };
+    let (result, source_map) = synth_spec(
+        transpiled,
+        SynthConfig {
+            private_prefix: options.private_prefix.clone(),
+            ..Default::default()
+        },
+    );
+    let mut ctx = ApolloCompiler::new();
+    ctx.add_type_system(&result, entry);
+    ctx.validate()
+        .iter()
+        .map(|apollo_diagnostic| {
+            to_lsp_diagnostic(
+                &reverse_diagnostic_map(apollo_diagnostic, &source_map),
+                entry,
+            )
+        })
+        .collect()
+}
+
+/// Parses, transpiles and validates the spec that has `entry` as its root file,
+/// reading the files opened in the editor from `documents`
+pub(crate) fn analyze(
+    entry: &Path,
+    documents: &HashMap<PathBuf, String>,
+    options: &TranspileSpecOptions,
+) -> Analysis {
+    let resolver = OverlaySourceResolver::new(documents);
+    let parsed = parse_spec_with_resolver(entry, &resolver);
+    let files = resolver.read();
+    let (spec, found) = match parsed {
+        Ok(spec) => {
+            let found = validate(&spec, entry, options);
+            (Some(spec), found)
+        }
+        Err(err) => (None, errors_to_diagnostics(err, entry)),
+    };
+
+    let mut diagnostics: HashMap<PathBuf, Vec<Diagnostic>> =
+        files.into_iter().map(|file| (file, vec![])).collect();
+    for (file, diagnostic) in found {
+        diagnostics.entry(file).or_default().push(diagnostic);
+    }
+    Analysis { spec, diagnostics }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn documents(sources: &[(&str, &str)]) -> HashMap<PathBuf, String> {
+        sources
+            .iter()
+            .map(|(path, content)| (PathBuf::from(path), content.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_transpile_options_from_client() {
+        let options = serde_json::json!({
+            "privatePrefix": "Private",
+            "inheritInterfaceFields": true,
+            "spreadDirectives": "key, tag",
+        });
+        let options = transpile_options(Some(&options));
+        assert_eq!(options.private_prefix, "Private");
+        assert!(options.inherit_interface_fields);
+        assert_eq!(
+            options.spread_directives,
+            DirectiveInheritance::Only(vec!["key".to_string(), "tag".to_string()])
+        );
+        assert_eq!(options.generic_directives, DirectiveInheritance::All);
+        assert_eq!(transpile_options(None).private_prefix, "_");
+    }
+
+    #[test]
+    fn test_no_diagnostics_for_valid_spec() {
+        let documents = documents(&[("/main.graphqxl", "type Query { foo: String }")]);
+        let analysis = analyze(
+            Path::new("/main.graphqxl"),
+            &documents,
+            &transpile_options(None),
+        );
+        assert!(analysis.spec.is_some());
+        assert_eq!(
+            analysis.diagnostics.get(Path::new("/main.graphqxl")),
+            Some(&vec![])
+        );
+    }
+
+    #[test]
+    fn test_transpile_error_in_imported_file() {
+        let documents = documents(&[
+            (
+                "/main.graphqxl",
+                "import \"other\"\ntype Query { ...Other }",
+            ),
+            ("/other.graphqxl", "type Other {\n  ...Missing\n}"),
+        ]);
+        let analysis = analyze(
+            Path::new("/main.graphqxl"),
+            &documents,
+            &transpile_options(None),
+        );
+        let diagnostics = analysis
+            .diagnostics
+            .get(Path::new("/other.graphqxl"))
+            .unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Missing is undefined");
+        assert_eq!(diagnostics[0].range.start, Position::new(1, 5));
+    }
+
+    #[test]
+    fn test_ranges_count_utf16_code_units() {
+        let documents = documents(&[("/main.graphqxl", "type Query @d(v: \"😀\") { ...Missing }")]);
+        let analysis = analyze(
+            Path::new("/main.graphqxl"),
+            &documents,
+            &transpile_options(None),
+        );
+        let diagnostics = analysis
+            .diagnostics
+            .get(Path::new("/main.graphqxl"))
+            .unwrap();
+        assert_eq!(diagnostics[0].message, "Missing is undefined");
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(0, 28), Position::new(0, 35))
+        );
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        let documents = documents(&[("/main.graphqxl", "type Query {\n  foo String\n}")]);
+        let analysis = analyze(
+            Path::new("/main.graphqxl"),
+            &documents,
+            &transpile_options(None),
+        );
+        assert!(analysis.spec.is_none());
+        let diagnostics = analysis
+            .diagnostics
+            .get(Path::new("/main.graphqxl"))
+            .unwrap();
+        assert_eq!(diagnostics[0].range.start.line, 1);
+    }
+
+    #[test]
+    fn test_apollo_error() {
+        let documents = documents(&[("/main.graphqxl", "type Query {\n  foo: Bar\n}")]);
+        let analysis = analyze(
+            Path::new("/main.graphqxl"),
+            &documents,
+            &transpile_options(None),
+        );
+        let diagnostics = analysis
+            .diagnostics
+            .get(Path::new("/main.graphqxl"))
+            .unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range.start, Position::new(1, 7));
+    }
+
+    #[test]
+    fn test_reports_all_errors() {
+        let documents = documents(&[(
+            "/main.graphqxl",
+            "type Query {\n  foo: Int\n  foo: Int\n  ...Missing\n}",
+        )]);
+        let analysis = analyze(
+            Path::new("/main.graphqxl"),
+            &documents,
+            &transpile_options(None),
+        );
+        let diagnostics = analysis
+            .diagnostics
+            .get(Path::new("/main.graphqxl"))
+            .unwrap();
+        assert_eq!(diagnostics.len(), 2);
+    }
+
+    #[test]
+    fn test_non_fatal_apollo_diagnostics_are_warnings() {
+        let documents = documents(&[(
+            "/main.graphqxl",
+            "type Query {\n  foo: Int\n}\ntype A @undefined {\n  a: Int\n}",
+        )]);
+        let analysis = analyze(
+            Path::new("/main.graphqxl"),
+            &documents,
+            &transpile_options(None),
+        );
+        let diagnostics = analysis
+            .diagnostics
+            .get(Path::new("/main.graphqxl"))
+            .unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+    }
+}
@@ -0,0 +1,123 @@
+mod analysis;
+mod overlay_source_resolver;
+mod server;
+mod symbol_index;
+mod text_position;
+
+use crate::lsp::analysis::transpile_options;
+use crate::lsp::server::Server;
+use anyhow::Result;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+    PublishDiagnostics,
+};
+use lsp_types::request::{Completion, GotoDefinition, HoverRequest, Request as _};
+use lsp_types::{
+    CompletionOptions, CompletionParams, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, GotoDefinitionParams, GotoDefinitionResponse, HoverParams,
+    HoverProviderCapability, InitializeParams, OneOf, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind,
+};
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        definition_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions::default()),
+        ..Default::default()
+    }
+}
+
+fn handle_request(server: &Server, request: Request) -> Result<Response> {
+    let id = request.id.clone();
+    let result = match request.method.as_str() {
+        GotoDefinition::METHOD => {
+            let params: GotoDefinitionParams = serde_json::from_value(request.params)?;
+            let position = params.text_document_position_params;
+            let location = server.definition(&position.text_document.uri, &position.position);
+            serde_json::to_value(location.map(GotoDefinitionResponse::Scalar))?
+        }
+        HoverRequest::METHOD => {
+            let params: HoverParams = serde_json::from_value(request.params)?;
+            let position = params.text_document_position_params;
+            serde_json::to_value(server.hover(&position.text_document.uri, &position.position))?
+        }
+        Completion::METHOD => {
+            let params: CompletionParams = serde_json::from_value(request.params)?;
+            serde_json::to_value(
+                server.completion(&params.text_document_position.text_document.uri),
+            )?
+        }
+        method => {
+            return Ok(Response::new_err(
+                id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unknown method {method}"),
+            ))
+        }
+    };
+    Ok(Response::new_ok(id, result))
+}
+
+fn handle_notification(server: &mut Server, notification: Notification) -> Result<Vec<Message>> {
+    let published = match notification.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            let params: DidOpenTextDocumentParams = serde_json::from_value(notification.params)?;
+            server.open(&params.text_document.uri, params.text_document.text)
+        }
+        DidChangeTextDocument::METHOD => {
+            let mut params: DidChangeTextDocumentParams =
+                serde_json::from_value(notification.params)?;
+            // the server only advertises full document sync
+            match params.content_changes.pop() {
+                Some(change) => server.open(&params.text_document.uri, change.text),
+                None => vec![],
+            }
+        }
+        DidCloseTextDocument::METHOD => {
+            let params: DidCloseTextDocumentParams = serde_json::from_value(notification.params)?;
+            server.close(&params.text_document.uri)
+        }
+        _ => vec![],
+    };
+    Ok(published
+        .into_iter()
+        .map(|params| {
+            Message::Notification(Notification::new(
+                PublishDiagnostics::METHOD.to_string(),
+                params,
+            ))
+        })
+        .collect())
+}
+
+/// Runs a language server over stdio until the client asks it to shut down
+pub(crate) fn run_lsp() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    let params = connection.initialize(serde_json::to_value(capabilities())?)?;
+    let params: InitializeParams = serde_json::from_value(params)?;
+
+    let mut server = Server::new(transpile_options(params.initialization_options.as_ref()));
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    break;
+                }
+                let response = handle_request(&server, request)?;
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                for message in handle_notification(&mut server, notification)? {
+                    connection.sender.send(message)?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use graphqxl_parser::{FsSourceResolver, InMemorySourceResolver, SourceResolver};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+/// Reads the documents opened in the editor from memory, as they might contain
+/// unsaved changes, and falls back to the filesystem for the rest of the imports
+pub(crate) struct OverlaySourceResolver {
+    memory: InMemorySourceResolver,
+    fs: FsSourceResolver,
+    read: RefCell<HashSet<PathBuf>>,
+}
+
+impl OverlaySourceResolver {
+    pub(crate) fn new(documents: &HashMap<PathBuf, String>) -> Self {
+        let mut memory = InMemorySourceResolver::new();
+        for (path, content) in documents.iter() {
+            memory = memory.source(&path.to_string_lossy(), content);
+        }
+        Self {
+            memory,
+            fs: FsSourceResolver,
+            read: RefCell::new(HashSet::new()),
+        }
+    }
+
+    /// Canonical paths of all the sources that have been read so far
+    pub(crate) fn read(&self) -> HashSet<PathBuf> {
+        self.read.borrow().clone()
+    }
+}
+
+impl SourceResolver for OverlaySourceResolver {
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        if self.memory.exists(path) {
+            self.memory.canonicalize(path)
+        } else {
+            self.fs.canonicalize(path)
+        }
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.memory.exists(path) || self.fs.exists(path)
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String, Box<dyn Error>> {
+        self.read.borrow_mut().insert(path.to_path_buf());
+        if self.memory.exists(path) {
+            self.memory.read_to_string(path)
+        } else {
+            self.fs.read_to_string(path)
+        }
+    }
+}
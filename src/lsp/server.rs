@@ -0,0 +1,241 @@
+use crate::lsp::analysis::analyze;
+use crate::lsp::symbol_index::{SymbolIndex, SymbolKind};
+use crate::lsp::text_position::{position_to_offset, span_to_range};
+use graphqxl_parser::Spec;
+use graphqxl_synthesizer::{synth_block_def, SynthConfig};
+use graphqxl_transpiler::{transpile_block, TranspileSpecOptions};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, Diagnostic, Hover, HoverContents, Location, MarkupContent,
+    MarkupKind, Position, PublishDiagnosticsParams, Url,
+};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Editor state: the content of every opened document and the last
+/// spec that could be parsed using each one of them as the entry point
+pub(crate) struct Server {
+    options: TranspileSpecOptions,
+    documents: HashMap<PathBuf, String>,
+    specs: HashMap<PathBuf, (Spec, SymbolIndex)>,
+    /// The files read while analyzing each opened document, itself included
+    imports: HashMap<PathBuf, HashSet<PathBuf>>,
+}
+
+impl Server {
+    pub(crate) fn new(options: TranspileSpecOptions) -> Self {
+        Self {
+            options,
+            documents: HashMap::new(),
+            specs: HashMap::new(),
+            imports: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn open(&mut self, uri: &Url, text: String) -> Vec<PublishDiagnosticsParams> {
+        let Ok(path) = uri.to_file_path() else {
+            return vec![];
+        };
+        self.documents.insert(path.clone(), text);
+        self.analyze_affected(&path)
+    }
+
+    pub(crate) fn close(&mut self, uri: &Url) -> Vec<PublishDiagnosticsParams> {
+        let Ok(path) = uri.to_file_path() else {
+            return vec![];
+        };
+        self.documents.remove(&path);
+        self.specs.remove(&path);
+        self.imports.remove(&path);
+        // the documents that import it now read it from the disk
+        let mut published = self.analyze_affected(&path);
+        published.push(PublishDiagnosticsParams::new(uri.clone(), vec![], None));
+        published
+    }
+
+    /// Documents can import each other, so a change in one of them might fix or
+    /// break the opened documents that import it, which are analyzed again with it
+    fn analyze_affected(&mut self, changed: &Path) -> Vec<PublishDiagnosticsParams> {
+        let mut published = vec![];
+        let paths: Vec<PathBuf> = self
+            .documents
+            .keys()
+            .filter(|path| {
+                *path == changed
+                    || self
+                        .imports
+                        .get(*path)
+                        .is_some_and(|imports| imports.contains(changed))
+            })
+            .cloned()
+            .collect();
+        for path in paths {
+            let mut analysis = analyze(&path, &self.documents, &self.options);
+            self.imports
+                .insert(path.clone(), analysis.diagnostics.keys().cloned().collect());
+            let diagnostics: Vec<Diagnostic> =
+                analysis.diagnostics.remove(&path).unwrap_or_default();
+            if let Some(spec) = analysis.spec {
+                let index = SymbolIndex::from(&spec);
+                self.specs.insert(path.clone(), (spec, index));
+            }
+            if let Ok(uri) = Url::from_file_path(&path) {
+                published.push(PublishDiagnosticsParams::new(uri, diagnostics, None));
+            }
+        }
+        published
+    }
+
+    fn symbol_at(&self, uri: &Url, position: &Position) -> Option<(&Spec, &SymbolIndex, &str)> {
+        let path = uri.to_file_path().ok()?;
+        let text = self.documents.get(&path)?;
+        let (spec, index) = self.specs.get(&path)?;
+        let offset = position_to_offset(text, position);
+        let name = index.symbol_at(&path.to_string_lossy(), offset)?;
+        Some((spec, index, name))
+    }
+
+    pub(crate) fn definition(&self, uri: &Url, position: &Position) -> Option<Location> {
+        let (_, index, name) = self.symbol_at(uri, position)?;
+        let definition = index.definition_of(name)?;
+        let uri = Url::from_file_path(&definition.span.file).ok()?;
+        Some(Location::new(uri, span_to_range(&definition.span)))
+    }
+
+    /// Shows the fully transpiled version of the hovered type, input, enum or interface
+    pub(crate) fn hover(&self, uri: &Url, position: &Position) -> Option<Hover> {
+        let (spec, _, name) = self.symbol_at(uri, position)?;
+        let block_def = transpile_block(spec, name, &self.options).ok()??;
+        let config = SynthConfig {
+            private_prefix: self.options.private_prefix.clone(),
+            ..Default::default()
+        };
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!("```graphql\n{}\n```", synth_block_def(block_def, config)),
+            }),
+            range: None,
+        })
+    }
+
+    pub(crate) fn completion(&self, uri: &Url) -> Vec<CompletionItem> {
+        let Some((_, index)) = uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| self.specs.get(&path))
+        else {
+            return vec![];
+        };
+        index
+            .type_definitions()
+            .map(|(name, definition)| {
+                let (kind, detail) = match definition.kind {
+                    SymbolKind::Type => (CompletionItemKind::CLASS, "type"),
+                    SymbolKind::Input => (CompletionItemKind::STRUCT, "input"),
+                    SymbolKind::Enum => (CompletionItemKind::ENUM, "enum"),
+                    SymbolKind::Interface => (CompletionItemKind::INTERFACE, "interface"),
+                    SymbolKind::Scalar => (CompletionItemKind::UNIT, "scalar"),
+                    SymbolKind::Union => (CompletionItemKind::CLASS, "union"),
+                    SymbolKind::Directive => (CompletionItemKind::FUNCTION, "directive"),
+                };
+                CompletionItem {
+                    label: name.clone(),
+                    kind: Some(kind),
+                    detail: Some(detail.to_string()),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsp::analysis::transpile_options;
+
+    const MAIN: &str = "import \"other\"\ntype Query {\n  ...Other\n}\n";
+    const OTHER: &str = "type Other {\n  foo: String\n}\n";
+
+    fn server() -> (Server, Url) {
+        let mut server = Server::new(transpile_options(None));
+        let main = Url::from_file_path("/virtual/main.graphqxl").unwrap();
+        let other = Url::from_file_path("/virtual/other.graphqxl").unwrap();
+        server.open(&other, OTHER.to_string());
+        server.open(&main, MAIN.to_string());
+        (server, main)
+    }
+
+    #[test]
+    fn test_definition_in_other_document() {
+        let (server, main) = server();
+        let location = server.definition(&main, &Position::new(2, 7)).unwrap();
+        assert_eq!(location.uri.path(), "/virtual/other.graphqxl");
+        assert_eq!(location.range.start, Position::new(0, 5));
+    }
+
+    #[test]
+    fn test_hover_shows_transpiled_block() {
+        let (server, main) = server();
+        let hover = server.hover(&main, &Position::new(1, 6)).unwrap();
+        let HoverContents::Markup(content) = hover.contents else {
+            panic!("expected markup")
+        };
+        assert!(content.value.contains("foo: String"));
+    }
+
+    #[test]
+    fn test_hover_includes_extensions() {
+        let (mut server, main) = server();
+        server.open(
+            &main,
+            format!("{MAIN}extend type Query {{\n  bar: Int\n}}\n"),
+        );
+        let hover = server.hover(&main, &Position::new(1, 6)).unwrap();
+        let HoverContents::Markup(content) = hover.contents else {
+            panic!("expected markup")
+        };
+        assert!(content.value.contains("foo: String"));
+        assert!(content.value.contains("bar: Int"));
+    }
+
+    #[test]
+    fn test_diagnostics_are_published_and_cleared() {
+        let (mut server, main) = server();
+        let published = server.open(&main, "type Query {\n  ...Missing\n}\n".to_string());
+        let main_diagnostics = published.iter().find(|p| p.uri == main).unwrap();
+        assert_eq!(main_diagnostics.diagnostics.len(), 1);
+        let published = server.open(&main, MAIN.to_string());
+        let main_diagnostics = published.iter().find(|p| p.uri == main).unwrap();
+        assert!(main_diagnostics.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_only_affected_documents_are_analyzed_again() {
+        let (mut server, main) = server();
+        let other = Url::from_file_path("/virtual/other.graphqxl").unwrap();
+        let unrelated = Url::from_file_path("/virtual/unrelated.graphqxl").unwrap();
+        let published = server.open(&unrelated, "type Unrelated { a: Int }".to_string());
+        let uris: Vec<&Url> = published.iter().map(|p| &p.uri).collect();
+        assert_eq!(uris, vec![&unrelated]);
+
+        let published = server.open(&other, "type Other {\n  ...Missing\n}\n".to_string());
+        let mut uris: Vec<&str> = published.iter().map(|p| p.uri.path()).collect();
+        uris.sort();
+        assert_eq!(uris, vec![main.path(), other.path()]);
+        let main_diagnostics = published.iter().find(|p| p.uri == main).unwrap();
+        assert!(main_diagnostics.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_completion() {
+        let (server, main) = server();
+        let mut labels: Vec<String> = server
+            .completion(&main)
+            .into_iter()
+            .map(|item| item.label)
+            .collect();
+        labels.sort();
+        assert_eq!(labels, vec!["Other", "Query"]);
+    }
+}
@@ -0,0 +1,279 @@
+use graphqxl_parser::{
+    Argument, BlockDef, BlockDefType, BlockEntry, Directive, ExpandableRef, Identifier,
+    ModifiedRef, OwnedSpan, Spec, UnionMember, ValueBasicType, ValueType,
+};
+use std::collections::HashMap;
+
+const DIRECTIVE_PREFIX: &str = "@";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SymbolKind {
+    Type,
+    Input,
+    Enum,
+    Interface,
+    Scalar,
+    Union,
+    Directive,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Definition {
+    pub(crate) kind: SymbolKind,
+    pub(crate) span: OwnedSpan,
+}
+
+/// Locations of every definition in a spec and of every identifier that references one
+#[derive(Debug, Default)]
+pub(crate) struct SymbolIndex {
+    definitions: HashMap<String, Definition>,
+    references: Vec<(String, OwnedSpan)>,
+}
+
+impl SymbolIndex {
+    pub(crate) fn from(spec: &Spec) -> Self {
+        let mut index = Self::default();
+        for (key, block_def) in spec.types.iter() {
+            index.block_def(key, block_def, SymbolKind::Type);
+        }
+        for (key, block_def) in spec.inputs.iter() {
+            index.block_def(key, block_def, SymbolKind::Input);
+        }
+        for (key, block_def) in spec.enums.iter() {
+            index.block_def(key, block_def, SymbolKind::Enum);
+        }
+        for (key, block_def) in spec.interfaces.iter() {
+            index.block_def(key, block_def, SymbolKind::Interface);
+        }
+        for generic_block_def in spec
+            .generic_types
+            .values()
+            .chain(spec.generic_inputs.values())
+            .chain(spec.generic_interfaces.values())
+        {
+            let kind = match generic_block_def.kind {
+                BlockDefType::Type => SymbolKind::Type,
+                BlockDefType::Input => SymbolKind::Input,
+                BlockDefType::Enum => SymbolKind::Enum,
+                BlockDefType::Interface => SymbolKind::Interface,
+            };
+            index.definition(&generic_block_def.name, kind);
+            index.directives(&generic_block_def.directives);
+            index.modified_ref(&generic_block_def.modified_ref);
+        }
+        for (key, scalar) in spec.scalars.iter() {
+            index.definition_or_extension(key, &scalar.name, SymbolKind::Scalar);
+            index.directives(&scalar.directives);
+        }
+        for (key, union) in spec.unions.iter() {
+            index.definition_or_extension(key, &union.name, SymbolKind::Union);
+            index.directives(&union.directives);
+            for member in union.members.iter() {
+                let (UnionMember::Type(member) | UnionMember::Spread(member)) = member;
+                index.reference(member);
+            }
+        }
+        for generic_union in spec.generic_unions.values() {
+            index.definition(&generic_union.name, SymbolKind::Union);
+            index.directives(&generic_union.directives);
+            index.expandable_ref(&generic_union.expandable_ref);
+        }
+        for directive_def in spec.directives.values() {
+            let mut name = directive_def.name.clone();
+            name.id = format!("{DIRECTIVE_PREFIX}{}", name.id);
+            index.definition(&name, SymbolKind::Directive);
+            index.args(&directive_def.arguments);
+        }
+        for schema in spec.schemas.values() {
+            index.directives(&schema.directives);
+            for root in [&schema.query, &schema.mutation, &schema.subscription] {
+                if !root.id.is_empty() {
+                    index.reference(root);
+                }
+            }
+        }
+        index
+    }
+
+    fn definition(&mut self, name: &Identifier, kind: SymbolKind) {
+        self.definitions.insert(
+            name.id.clone(),
+            Definition {
+                kind,
+                span: name.span.clone(),
+            },
+        );
+    }
+
+    fn definition_or_extension(&mut self, key: &str, name: &Identifier, kind: SymbolKind) {
+        if Spec::is_extension_key(key) {
+            self.reference(name)
+        } else {
+            self.definition(name, kind)
+        }
+    }
+
+    fn reference(&mut self, name: &Identifier) {
+        self.references.push((name.id.clone(), name.span.clone()));
+    }
+
+    fn block_def(&mut self, key: &str, block_def: &BlockDef, kind: SymbolKind) {
+        self.definition_or_extension(key, &block_def.name, kind);
+        self.directives(&block_def.directives);
+        if let Some(implements) = &block_def.implements {
+            for interface in implements.interfaces.iter() {
+                self.reference(interface);
+            }
+        }
+        for entry in block_def.entries.iter() {
+            match entry {
+                BlockEntry::Field(field) => {
+                    if let Some(value_type) = &field.value_type {
+                        self.value_type(value_type);
+                    }
+                    self.args(&field.args);
+                    self.directives(&field.directives);
+                }
+                BlockEntry::SpreadRef(modified_ref) => self.modified_ref(modified_ref),
+            }
+        }
+    }
+
+    fn modified_ref(&mut self, modified_ref: &ModifiedRef) {
+        match modified_ref {
+            ModifiedRef::Required(inner, _) => self.modified_ref(inner),
+            ModifiedRef::Optional(inner, _) => self.modified_ref(inner),
+            ModifiedRef::Omit(inner, _, _) => self.modified_ref(inner),
+            ModifiedRef::Pick(inner, _, _) => self.modified_ref(inner),
+            ModifiedRef::ExpandableRef(expandable_ref) => self.expandable_ref(expandable_ref),
+        }
+    }
+
+    fn expandable_ref(&mut self, expandable_ref: &ExpandableRef) {
+        self.reference(&expandable_ref.identifier);
+        if let Some(generic_call) = &expandable_ref.generic_call {
+            for arg in generic_call.args.iter() {
+                self.value_type(arg);
+            }
+        }
+    }
+
+    fn value_type(&mut self, value_type: &ValueType) {
+        match value_type.retrieve_basic_type() {
+            ValueBasicType::Object(name) => self.reference(name),
+            ValueBasicType::Generic(expandable_ref) => self.expandable_ref(expandable_ref),
+            _ => {}
+        }
+    }
+
+    fn args(&mut self, args: &[Argument]) {
+        for arg in args.iter() {
+            self.value_type(&arg.value_type);
+            self.directives(&arg.directives);
+        }
+    }
+
+    fn directives(&mut self, directives: &[Directive]) {
+        for directive in directives.iter() {
+            let mut name = directive.name.clone();
+            name.id = format!("{DIRECTIVE_PREFIX}{}", name.id);
+            self.reference(&name);
+        }
+    }
+
+    /// Name of the symbol, either a definition or a reference, found at
+    /// the byte `offset` of `file`
+    pub(crate) fn symbol_at(&self, file: &str, offset: usize) -> Option<&str> {
+        let contains =
+            |span: &OwnedSpan| span.file == file && span.start <= offset && offset <= span.end;
+        for (name, span) in self.references.iter() {
+            if contains(span) {
+                return Some(name);
+            }
+        }
+        for (name, definition) in self.definitions.iter() {
+            if contains(&definition.span) {
+                return Some(name);
+            }
+        }
+        None
+    }
+
+    pub(crate) fn definition_of(&self, name: &str) -> Option<&Definition> {
+        self.definitions.get(name)
+    }
+
+    /// Every definition that can be used as a type, directives excluded
+    pub(crate) fn type_definitions(&self) -> impl Iterator<Item = (&String, &Definition)> {
+        self.definitions
+            .iter()
+            .filter(|(_, definition)| definition.kind != SymbolKind::Directive)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphqxl_parser::{parse_spec_with_resolver, InMemorySourceResolver};
+
+    const MAIN: &str = "\
+import \"other\"
+type Generic<T> { t: T }
+type Foo @dir {
+    ...Bar
+    ...Generic<Baz>
+    baz(arg: Baz): [Baz!]!
+}
+extend type Foo { other: Int }
+directive @dir on OBJECT
+";
+
+    fn index() -> SymbolIndex {
+        let resolver = InMemorySourceResolver::new()
+            .source("main.graphqxl", MAIN)
+            .source("other.graphqxl", "type Bar { a: Int }\nenum Baz { A }");
+        SymbolIndex::from(&parse_spec_with_resolver("main.graphqxl", &resolver).unwrap())
+    }
+
+    fn nth_offset(text: &str, pattern: &str, n: usize) -> usize {
+        text.match_indices(pattern).nth(n).unwrap().0 + 1
+    }
+
+    #[test]
+    fn test_finds_spread_reference() {
+        let index = index();
+        let name = index.symbol_at("/main.graphqxl", nth_offset(MAIN, "Bar", 0));
+        assert_eq!(name, Some("Bar"));
+        let definition = index.definition_of("Bar").unwrap();
+        assert_eq!(definition.span.file, "/other.graphqxl");
+        assert_eq!(definition.kind, SymbolKind::Type);
+    }
+
+    #[test]
+    fn test_finds_generic_call_and_argument_references() {
+        let index = index();
+        for n in 0..3 {
+            let name = index.symbol_at("/main.graphqxl", nth_offset(MAIN, "Baz", n));
+            assert_eq!(name, Some("Baz"));
+        }
+        assert_eq!(index.definition_of("Baz").unwrap().kind, SymbolKind::Enum);
+    }
+
+    #[test]
+    fn test_finds_directive_and_extension_references() {
+        let index = index();
+        let name = index.symbol_at("/main.graphqxl", nth_offset(MAIN, "@dir", 0));
+        assert_eq!(name, Some("@dir"));
+        let name = index.symbol_at("/main.graphqxl", nth_offset(MAIN, "Foo", 1));
+        assert_eq!(name, Some("Foo"));
+        assert_eq!(index.definition_of("Foo").unwrap().span.line, 3);
+    }
+
+    #[test]
+    fn test_lists_type_definitions() {
+        let index = index();
+        let mut names: Vec<&String> = index.type_definitions().map(|(name, _)| name).collect();
+        names.sort();
+        assert_eq!(names, vec!["Bar", "Baz", "Foo", "Generic"]);
+    }
+}
@@ -0,0 +1,86 @@
+use crate::source_map::utf16_col;
+use graphqxl_parser::OwnedSpan;
+use lsp_types::{Position, Range};
+
+/// Translates an LSP position, which counts characters in UTF-16 code units,
+/// into a byte offset inside `text`
+pub(crate) fn position_to_offset(text: &str, position: &Position) -> usize {
+    let mut offset = 0;
+    for (i, line) in text.split_inclusive('\n').enumerate() {
+        if i == position.line as usize {
+            let mut utf16_col = 0;
+            for (byte_col, char) in line.char_indices() {
+                if utf16_col >= position.character as usize || char == '\n' {
+                    return offset + byte_col;
+                }
+                utf16_col += char.len_utf16();
+            }
+            return offset + line.len();
+        }
+        offset += line.len();
+    }
+    text.len()
+}
+
+/// Computes the LSP range covered by a span using its own line and column
+/// information, so the source file does not need to be read again
+pub(crate) fn span_to_range(span: &OwnedSpan) -> Range {
+    let start = Position::new(
+        span.line.saturating_sub(1) as u32,
+        utf16_col(span.err_placeholder.line(), span.col) as u32,
+    );
+    let end = match span.input.rsplit_once('\n') {
+        Some((before, last_line)) => Position::new(
+            start.line + before.matches('\n').count() as u32 + 1,
+            last_line.encode_utf16().count() as u32,
+        ),
+        None => Position::new(
+            start.line,
+            start.character + span.input.encode_utf16().count() as u32,
+        ),
+    };
+    Range::new(start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphqxl_parser::{parse_spec_with_resolver, BlockEntry, InMemorySourceResolver};
+
+    #[test]
+    fn test_position_to_offset() {
+        let text = "type A {\n  ñame: String\n}";
+        assert_eq!(position_to_offset(text, &Position::new(0, 5)), 5);
+        assert_eq!(position_to_offset(text, &Position::new(1, 3)), 13);
+        assert_eq!(position_to_offset(text, &Position::new(1, 100)), 24);
+        assert_eq!(position_to_offset(text, &Position::new(5, 0)), text.len());
+    }
+
+    #[test]
+    fn test_span_to_range() {
+        let span = OwnedSpan {
+            line: 2,
+            col: 3,
+            input: "foo {\n  bar\n}".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            span_to_range(&span),
+            Range::new(Position::new(1, 2), Position::new(3, 1))
+        );
+    }
+
+    #[test]
+    fn test_span_to_range_counts_utf16_code_units() {
+        let resolver = InMemorySourceResolver::new()
+            .source("spec.graphqxl", "type A @d(v: \"😀\") { a: Int }");
+        let spec = parse_spec_with_resolver("spec.graphqxl", &resolver).unwrap();
+        let BlockEntry::Field(field) = &spec.types["A"].entries[0] else {
+            panic!("expected a field")
+        };
+        assert_eq!(
+            span_to_range(&field.name.span),
+            Range::new(Position::new(0, 21), Position::new(0, 22))
+        );
+    }
+}
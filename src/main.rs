@@ -1,21 +1,53 @@
 mod apollo_diagnostic_source;
+mod diagnostics;
+mod diff;
+mod lsp;
 mod ok_or_anyhow_err;
+mod source_map;
+mod watch;
 
-use crate::apollo_diagnostic_source::{is_fatal_diagnostic, reverse_diagnostic_map};
+use crate::apollo_diagnostic_source::reverse_diagnostic_map;
+use crate::diagnostics::{report_error, report_warning, Diagnostics, MessageFormat};
+use crate::diff::{run_diff, DiffArgs};
+use crate::lsp::run_lsp;
 use crate::ok_or_anyhow_err::ok_or_anyhow_err;
+use crate::source_map::source_map_v3;
+use crate::watch::watch;
 use anyhow::Result;
 use apollo_compiler::ApolloCompiler;
-use clap::Parser;
-use graphqxl_parser::parse_spec;
+use clap::{Parser, Subcommand};
+use graphqxl_parser::{
+    parse_spec_with_resolver, FsSourceResolver, MultipleErrors, Severity, SourceResolver,
+};
 use graphqxl_synthesizer::{synth_spec, SynthConfig};
-use graphqxl_transpiler::{transpile_spec, TranspileSpecOptions};
+use graphqxl_transpiler::{transpile_spec, DirectiveInheritance, TranspileSpecOptions};
 use std::fs;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    args: Args,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    #[command(about = "Start a Language Server Protocol server for .graphqxl files over stdio")]
+    Lsp,
+    #[command(
+        about = "Report the breaking, dangerous and safe changes between two .graphqxl files"
+    )]
+    Diff(DiffArgs),
+}
+
+#[derive(clap::Args, Debug)]
 struct Args {
-    #[arg(help = "Path to the .graphqxl file")]
-    input: String,
+    #[arg(required = true, help = "Path to the .graphqxl file")]
+    input: Option<String>,
 
     #[arg(short, long, help = "Output path for the generated .graphql file")]
     output: Option<String>,
@@ -33,24 +65,104 @@ struct Args {
         help = "String that needs to be prefixed to a type or an input in order to consider it private"
     )]
     private_prefix: String,
+
+    #[arg(
+        long,
+        help = "Remove every definition and directive that cannot be reached from the schema roots"
+    )]
+    tree_shake: bool,
+
+    #[arg(
+        long,
+        help = "Add to types and interfaces the fields of the interfaces they implement that they do not declare"
+    )]
+    inherit_interface_fields: bool,
+
+    #[arg(
+        long,
+        default_value_t = DirectiveInheritance::None,
+        help = "Directives inherited from the definitions included with spreads: none, all or a comma separated list of names"
+    )]
+    spread_directives: DirectiveInheritance,
+
+    #[arg(
+        long,
+        default_value_t = DirectiveInheritance::All,
+        help = "Directives inherited from the definitions instantiated by generics: none, all or a comma separated list of names"
+    )]
+    generic_directives: DirectiveInheritance,
+
+    #[arg(
+        short,
+        long,
+        help = "Keep running and recompile every time the input file or any of its imports change"
+    )]
+    watch: bool,
+
+    #[arg(
+        long,
+        help = "Also write a Source Map v3 file, next to the generated .graphql file, that maps it back to the .graphqxl sources"
+    )]
+    source_map: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = MessageFormat::Human,
+        help = "Format of the errors and warnings"
+    )]
+    message_format: MessageFormat,
+}
+
+impl Args {
+    fn input(&self) -> &str {
+        // clap guarantees that it is present if no subcommand was provided
+        self.input.as_deref().unwrap_or_default()
+    }
+}
+
+#[derive(Debug)]
+struct Output {
+    result: String,
+    out_path: String,
+    source_map: Option<String>,
 }
 
-fn graphqxl_to_graphql(args: &Args) -> Result<(String, String)> {
+impl Output {
+    fn source_map_path(&self) -> String {
+        format!("{}.map", self.out_path)
+    }
+
+    fn write(&self) -> std::io::Result<()> {
+        fs::write(&self.out_path, &self.result)?;
+        if let Some(source_map) = &self.source_map {
+            fs::write(self.source_map_path(), source_map)?;
+        }
+        Ok(())
+    }
+}
+
+fn graphqxl_to_graphql(args: &Args, resolver: &dyn SourceResolver) -> Result<Output> {
+    let input = args.input();
     let out_path = if let Some(out_path) = &args.output {
         out_path.to_string()
-    } else if args.input.ends_with("graphqxl") {
-        args.input[..args.input.len() - 2].to_string() + "l"
+    } else if input.ends_with("graphqxl") {
+        input[..input.len() - 2].to_string() + "l"
     } else {
-        args.input.to_string() + ".graphql"
+        input.to_string() + ".graphql"
     };
 
-    let spec_result = parse_spec(&args.input);
+    let spec_result = parse_spec_with_resolver(input, resolver);
     let spec = ok_or_anyhow_err(spec_result, "Could not parse GraphQXL spec")?;
 
     let transpile_result = transpile_spec(
         &spec,
         &TranspileSpecOptions {
             private_prefix: args.private_prefix.clone(),
+            tree_shake: args.tree_shake,
+            inherit_interface_fields: args.inherit_interface_fields,
+            spread_directives: args.spread_directives.clone(),
+            generic_directives: args.generic_directives.clone(),
         },
     );
     let transpiled = ok_or_anyhow_err(transpile_result, "Could not transpile graphqxl spec")?;
@@ -65,19 +177,47 @@ fn graphqxl_to_graphql(args: &Args) -> Result<(String, String)> {
     );
     let mut ctx = ApolloCompiler::new();
     ctx.add_type_system(&result, &out_path);
-    let diagnostics = ctx.validate();
-    for diagnostic in diagnostics {
-        if is_fatal_diagnostic(&diagnostic) {
-            reverse_diagnostic_map(&diagnostic, &source_map)?;
+    let mut errors = MultipleErrors::new();
+    for diagnostic in ctx.validate() {
+        let diagnostic = reverse_diagnostic_map(&diagnostic, &source_map);
+        match diagnostic.severity {
+            Severity::Error => errors.push(Box::new(diagnostic)),
+            Severity::Warning => report_warning(args.message_format, &diagnostic),
         }
     }
-    Ok((result, out_path))
+    if let Err(err) = errors.into_result(()) {
+        return Err(Diagnostics::new(None, err).into());
+    }
+    let source_map = if args.source_map {
+        Some(source_map_v3(&result, &out_path, &source_map))
+    } else {
+        None
+    };
+    Ok(Output {
+        result,
+        out_path,
+        source_map,
+    })
 }
 
 fn main() -> Result<()> {
-    let args = Args::parse();
-    let (result, out_path) = graphqxl_to_graphql(&args)?;
-    fs::write(out_path, result)?;
+    let cli = Cli::parse();
+    match &cli.command {
+        Some(Command::Lsp) => return run_lsp(),
+        Some(Command::Diff(diff_args)) => return run_diff(diff_args, &FsSourceResolver),
+        None => {}
+    }
+    let args = cli.args;
+    if args.watch {
+        watch(&args);
+    }
+    match graphqxl_to_graphql(&args, &FsSourceResolver) {
+        Ok(output) => output.write()?,
+        Err(err) => {
+            report_error(args.message_format, &err);
+            std::process::exit(1)
+        }
+    }
     Ok(())
 }
 
@@ -89,10 +229,29 @@ mod tests {
 
     const ONLY: &str = "";
 
+    impl Args {
+        pub(crate) fn from_input(input: &str) -> Self {
+            Self {
+                input: Some(input.to_string()),
+                output: None,
+                indent_spaces: 2,
+                private_prefix: "_".to_string(),
+                tree_shake: false,
+                inherit_interface_fields: false,
+                spread_directives: DirectiveInheritance::None,
+                generic_directives: DirectiveInheritance::All,
+                watch: false,
+                source_map: false,
+                message_format: MessageFormat::Human,
+            }
+        }
+    }
+
     #[test]
     fn test_graphqxl_to_graphql() {
         let test_dir = Path::new("src").join("test");
         let paths = fs::read_dir(&test_dir).unwrap();
+        let re = Regex::new(r"(/.+)+\.graphqxl").unwrap();
         for dir_entry in paths {
             let file_name = dir_entry.unwrap().file_name();
             let path = file_name.to_str().unwrap();
@@ -102,17 +261,14 @@ mod tests {
             {
                 continue;
             }
-            let result = graphqxl_to_graphql(&Args {
-                input: test_dir.join(path).to_str().unwrap().to_string(),
-                output: None,
-                indent_spaces: 2,
-                private_prefix: "_".to_string(),
-            });
-            let result = if let Ok((result, _)) = result {
-                result
+            let result = graphqxl_to_graphql(
+                &Args::from_input(test_dir.join(path).to_str().unwrap()),
+                &FsSourceResolver,
+            );
+            let result = if let Ok(output) = result {
+                output.result
             } else {
                 let err = format!("{}", result.unwrap_err());
-                let re = Regex::new(r"(/.+)+\.graphqxl").unwrap();
                 re.replace_all(&err, "").to_string()
             };
             let out_path = test_dir.join(path.to_string() + ".result");
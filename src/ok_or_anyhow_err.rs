@@ -1,4 +1,5 @@
-use anyhow::{anyhow, Result};
+use crate::diagnostics::Diagnostics;
+use anyhow::Result;
 use std::error::Error;
 
 pub(crate) fn ok_or_anyhow_err<T>(
@@ -7,6 +8,6 @@ pub(crate) fn ok_or_anyhow_err<T>(
 ) -> Result<T> {
     match result {
         Ok(ok) => Ok(ok),
-        Err(err) => Err(anyhow!("{}:\n\n{}", msg, err)),
+        Err(err) => Err(Diagnostics::new(Some(msg), err).into()),
     }
 }
@@ -0,0 +1,218 @@
+use graphqxl_synthesizer::SourceMapEntry;
+use serde_json::json;
+use std::path::{Component, Path, PathBuf};
+
+const BASE64_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn encode_vlq(value: i64, out: &mut String) {
+    let mut vlq = if value < 0 {
+        ((-value) << 1) | 1
+    } else {
+        value << 1
+    };
+    loop {
+        let mut digit = vlq & 0b11111;
+        vlq >>= 5;
+        if vlq > 0 {
+            digit |= 0b100000;
+        }
+        out.push(BASE64_CHARS[digit as usize] as char);
+        if vlq == 0 {
+            break;
+        }
+    }
+}
+
+/// Zero based line and column of a byte offset in `text`. Source maps count columns
+/// in UTF-16 code units, as JavaScript strings do.
+fn line_col(line_starts: &[usize], text: &str, offset: usize) -> (usize, usize) {
+    let line = match line_starts.binary_search(&offset) {
+        Ok(line) => line,
+        Err(next_line) => next_line - 1,
+    };
+    let col = text[line_starts[line]..offset].encode_utf16().count();
+    (line, col)
+}
+
+/// Zero based UTF-16 column of the one based column in characters that pest gives
+/// for a position of `line`. If `line` is not known up to that position, the missing
+/// characters count as one code unit each.
+pub(crate) fn utf16_col(line: &str, col: usize) -> usize {
+    let chars = col.saturating_sub(1);
+    let known: usize = line.chars().take(chars).map(char::len_utf16).sum();
+    known + chars.saturating_sub(line.chars().count())
+}
+
+/// Path of `source` relative to the `dir` where the source map is written, so that the
+/// map does not depend on the machine where it was generated
+fn relative_source(source: &str, dir: &Path) -> String {
+    let source = Path::new(source);
+    if !source.is_absolute() || !dir.is_absolute() {
+        return source.to_string_lossy().to_string();
+    }
+    let source_components: Vec<Component> = source.components().collect();
+    let dir_components: Vec<Component> = dir.components().collect();
+    let common = source_components
+        .iter()
+        .zip(dir_components.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut relative = PathBuf::new();
+    for _ in common..dir_components.len() {
+        relative.push("..");
+    }
+    for component in &source_components[common..] {
+        relative.push(component);
+    }
+    relative.to_string_lossy().to_string()
+}
+
+/// Builds a Source Map v3 JSON that maps every position of the `generated` GraphQL
+/// file, written in `out_path`, back to the line and column of the .graphqxl file
+/// where it was defined
+pub(crate) fn source_map_v3(generated: &str, out_path: &str, entries: &[SourceMapEntry]) -> String {
+    let out_dir = Path::new(out_path).parent().unwrap_or(Path::new(""));
+    let out_dir = match std::env::current_dir() {
+        Ok(cwd) => cwd.join(out_dir),
+        Err(_) => out_dir.to_path_buf(),
+    };
+
+    let mut line_starts = vec![0];
+    line_starts.extend(generated.match_indices('\n').map(|(i, _)| i + 1));
+
+    // when several entries start at the same place, the most specific one wins
+    let mut entries: Vec<&SourceMapEntry> = entries
+        .iter()
+        .filter(|entry| !entry.span.file.is_empty() && entry.start <= generated.len())
+        .collect();
+    entries.sort_by_key(|entry| (entry.start, entry.stop - entry.start));
+    entries.dedup_by_key(|entry| entry.start);
+
+    let mut sources: Vec<String> = vec![];
+    let mut mappings = String::new();
+    let mut current_line = 0;
+    let mut prev_col = 0;
+    let mut prev_source = 0;
+    let mut prev_src_line = 0;
+    let mut prev_src_col = 0;
+    for entry in entries {
+        let (line, col) = line_col(&line_starts, generated, entry.start);
+        if line != current_line {
+            mappings.push_str(&";".repeat(line - current_line));
+            current_line = line;
+            prev_col = 0;
+        } else if !mappings.is_empty() && !mappings.ends_with(';') {
+            mappings.push(',');
+        }
+        let file = relative_source(&entry.span.file, &out_dir);
+        let source = match sources.iter().position(|s| s == &file) {
+            Some(source) => source,
+            None => {
+                sources.push(file);
+                sources.len() - 1
+            }
+        };
+        // pest positions are one based, source maps are zero based
+        let src_line = entry.span.line.saturating_sub(1);
+        let src_col = utf16_col(entry.span.err_placeholder.line(), entry.span.col);
+        encode_vlq(col as i64 - prev_col as i64, &mut mappings);
+        encode_vlq(source as i64 - prev_source as i64, &mut mappings);
+        encode_vlq(src_line as i64 - prev_src_line as i64, &mut mappings);
+        encode_vlq(src_col as i64 - prev_src_col as i64, &mut mappings);
+        prev_col = col;
+        prev_source = source;
+        prev_src_line = src_line;
+        prev_src_col = src_col;
+    }
+
+    let file = Path::new(out_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    json!({
+        "version": 3,
+        "file": file,
+        "sources": sources,
+        "names": [],
+        "mappings": mappings,
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphqxl_parser::{parse_spec_with_resolver, BlockEntry, InMemorySourceResolver};
+    use graphqxl_synthesizer::{synth_spec, SynthConfig};
+    use graphqxl_transpiler::{transpile_spec, TranspileSpecOptions};
+
+    #[test]
+    fn test_encodes_vlq() {
+        let mut out = String::new();
+        for value in [0, 1, -1, 15, 16, -16, 123] {
+            encode_vlq(value, &mut out);
+            out.push(' ');
+        }
+        assert_eq!(out, "A C D e gB hB 2H ")
+    }
+
+    #[test]
+    fn test_counts_columns_in_utf16_code_units() {
+        let text = "a\né😀b";
+        assert_eq!(line_col(&[0, 2], text, text.len() - 1), (1, 3));
+        assert_eq!(utf16_col("é😀b", 3), 3);
+        assert_eq!(utf16_col("", 1), 0);
+        assert_eq!(utf16_col("😀", 4), 4);
+
+        let resolver = InMemorySourceResolver::new()
+            .source("spec.graphqxl", "type A @d(v: \"😀\") { a: Int }");
+        let spec = parse_spec_with_resolver("spec.graphqxl", &resolver).unwrap();
+        let BlockEntry::Field(field) = &spec.types["A"].entries[0] else {
+            panic!("expected a field")
+        };
+        // "😀" is one character in pest columns, but two UTF-16 code units
+        assert_eq!(field.span.col, 21);
+        assert_eq!(
+            utf16_col(field.span.err_placeholder.line(), field.span.col),
+            21
+        );
+    }
+
+    #[test]
+    fn test_makes_sources_relative_to_the_map() {
+        assert_eq!(
+            relative_source("/project/schema/users.graphqxl", Path::new("/project/out")),
+            "../schema/users.graphqxl"
+        );
+        assert_eq!(
+            relative_source("/project/users.graphqxl", Path::new("/project")),
+            "users.graphqxl"
+        );
+    }
+
+    #[test]
+    fn test_maps_generated_positions_to_the_source() {
+        let resolver = InMemorySourceResolver::new().source(
+            "spec.graphqxl",
+            "type A {\n  a: Int\n}\n\ntype B {\n  ...A\n  b: Int\n}",
+        );
+        let spec = parse_spec_with_resolver("spec.graphqxl", &resolver).unwrap();
+        let options = TranspileSpecOptions {
+            private_prefix: "_".to_string(),
+            ..Default::default()
+        };
+        let transpiled = transpile_spec(&spec, &options).unwrap();
+        let (generated, entries) = synth_spec(transpiled, SynthConfig::default());
+        let source_map: serde_json::Value =
+            serde_json::from_str(&source_map_v3(&generated, "/out/spec.graphql", &entries))
+                .unwrap();
+        assert_eq!(source_map["version"], 3);
+        assert_eq!(source_map["file"], "spec.graphql");
+        assert_eq!(source_map["sources"], json!(["../spec.graphqxl"]));
+        // the spread field "a" of type B points back to the field declared in type A
+        assert_eq!(
+            source_map["mappings"],
+            "KAAK;EACH,GAAG;;;KAGA;EAHH,GAAG;EAKH,GAAG"
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use crate::diagnostics::report_error;
+use crate::{graphqxl_to_graphql, Args};
+use graphqxl_parser::{FsSourceResolver, SourceResolver};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Filesystem resolver that remembers every path the parser looked at, including
+/// imports that do not exist yet, so that the full import graph can be watched
+#[derive(Default)]
+pub(crate) struct WatchedSourceResolver {
+    inner: FsSourceResolver,
+    seen: RefCell<HashSet<PathBuf>>,
+}
+
+impl WatchedSourceResolver {
+    pub(crate) fn seen(&self) -> HashSet<PathBuf> {
+        self.seen.borrow().clone()
+    }
+
+    fn see(&self, path: &Path) {
+        self.seen.borrow_mut().insert(path.to_path_buf());
+    }
+}
+
+impl SourceResolver for WatchedSourceResolver {
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        self.see(path);
+        self.inner.canonicalize(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.see(path);
+        self.inner.exists(path)
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String, Box<dyn Error>> {
+        self.see(path);
+        self.inner.read_to_string(path)
+    }
+}
+
+fn snapshot(paths: &HashSet<PathBuf>) -> HashMap<PathBuf, Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| {
+            let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+            (path.clone(), modified)
+        })
+        .collect()
+}
+
+fn compile(args: &Args) -> HashSet<PathBuf> {
+    let resolver = WatchedSourceResolver::default();
+    match graphqxl_to_graphql(args, &resolver) {
+        Ok(output) => match output.write() {
+            Ok(_) => println!("Compiled {}", output.out_path),
+            Err(err) => eprintln!("Could not write {}: {}", output.out_path, err),
+        },
+        Err(err) => report_error(args.message_format, &err),
+    }
+    let mut seen = resolver.seen();
+    // the entry point is always watched, even if it could not be read
+    seen.insert(PathBuf::from(args.input()));
+    seen
+}
+
+pub(crate) fn watch(args: &Args) -> ! {
+    loop {
+        let watched = compile(args);
+        let last = snapshot(&watched);
+        while snapshot(&watched) == last {
+            sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_the_full_import_graph() {
+        let resolver = WatchedSourceResolver::default();
+        let args = Args::from_input("src/test/file.graphqxl");
+        graphqxl_to_graphql(&args, &resolver).unwrap();
+        let seen: Vec<String> = resolver
+            .seen()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert!(seen.contains(&"file.graphqxl".to_string()));
+        assert!(seen.contains(&"_other.graphqxl".to_string()));
+    }
+
+    #[test]
+    fn test_records_missing_imports() {
+        let resolver = WatchedSourceResolver::default();
+        assert!(!resolver.exists(Path::new("src/test/missing.graphqxl")));
+        assert!(resolver
+            .seen()
+            .contains(&PathBuf::from("src/test/missing.graphqxl")));
+    }
+}